@@ -1,20 +1,61 @@
 use crate::ir::{IrProgram, IrFunction, OwnershipState, BorrowKind};
 use crate::parser::HeaderCache;
-use std::collections::{HashMap, HashSet};
+use petgraph::graph::NodeIndex;
+use petgraph::Direction;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 pub mod ownership;
-pub mod borrows;
 pub mod lifetimes;
 pub mod lifetime_checker;
 pub mod scope_lifetime;
 pub mod lifetime_inference;
+pub mod pointer_safety;
+pub mod unsafe_propagation;
+pub mod unsafe_audit;
+pub mod unnecessary_unsafe;
+pub mod move_paths;
 
+use move_paths::{MoveCheckResult, Place};
+
+/// A single borrow-check violation, in rustc's `explain_borrow` shape: a
+/// primary message/location plus zero or more secondary ("label",
+/// "location") spans -- e.g. ("'r' was borrowed at statement 1", "statement 1") and
+/// ("borrow later used here", "statement 3") for a borrow conflict -- so a
+/// caller can show the full triangle of conflicting-borrow / existing-borrow
+/// / later-use instead of one flat line. `check_function` builds these
+/// structurally; `check_borrows` and its siblings flatten them with
+/// [`BorrowCheckError::render`] to keep their existing `Vec<String>`
+/// contract for callers that mix in other checks' plain-string errors.
 #[derive(Debug, Clone)]
-#[allow(dead_code)]
 pub struct BorrowCheckError {
     pub kind: ErrorKind,
     pub location: String,
     pub message: String,
+    pub secondary: Vec<(String, String)>,
+}
+
+impl BorrowCheckError {
+    fn new(kind: ErrorKind, location: usize, message: String) -> Self {
+        Self {
+            kind,
+            location: format!("statement {}", location),
+            message,
+            secondary: Vec::new(),
+        }
+    }
+
+    fn with_secondary(mut self, secondary: Vec<(String, String)>) -> Self {
+        self.secondary = secondary;
+        self
+    }
+
+    fn render(&self) -> String {
+        let mut rendered = format!("{} ({})", self.message, self.location);
+        for (label, location) in &self.secondary {
+            rendered.push_str(&format!("\nnote: {} ({})", label, location));
+        }
+        rendered
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -25,16 +66,19 @@ pub enum ErrorKind {
     MutableBorrowWhileImmutable,
     DanglingReference,
     LifetimeViolation,
+    MoveThroughReference,
+    ConstReferenceAssignment,
 }
 
 pub fn check_borrows(program: IrProgram) -> Result<Vec<String>, String> {
     let mut errors = Vec::new();
-    
+
     for function in &program.functions {
-        let function_errors = check_function(function)?;
-        errors.extend(function_errors);
+        let (function_errors, function_warnings) = check_function(function)?;
+        errors.extend(function_errors.iter().map(BorrowCheckError::render));
+        errors.extend(function_warnings.into_iter().map(|w| format!("warning: {}", w)));
     }
-    
+
     Ok(errors)
 }
 
@@ -77,10 +121,11 @@ pub fn check_borrows_with_safety_context(
         }
         eprintln!("DEBUG: Function '{}' is safe, checking...", function.name);
         
-        let function_errors = check_function(function)?;
-        errors.extend(function_errors);
+        let (function_errors, function_warnings) = check_function(function)?;
+        errors.extend(function_errors.iter().map(BorrowCheckError::render));
+        errors.extend(function_warnings.into_iter().map(|w| format!("warning: {}", w)));
     }
-    
+
     // Run lifetime inference and validation for safe functions
     for function in &program.functions {
         if safety_context.should_check_function(&function.name) {
@@ -91,8 +136,9 @@ pub fn check_borrows_with_safety_context(
     
     // If we have header annotations, also check lifetime constraints
     if header_cache.has_signatures() {
-        let lifetime_errors = lifetime_checker::check_lifetimes_with_annotations(&program, &header_cache)?;
+        let (lifetime_errors, lifetime_warnings) = lifetime_checker::check_lifetimes_with_annotations(&program, &header_cache)?;
         errors.extend(lifetime_errors);
+        errors.extend(lifetime_warnings.into_iter().map(|w| format!("warning: {}", w)));
         
         // Also run scope-based lifetime checking
         let scope_errors = scope_lifetime::check_scoped_lifetimes(&program, &header_cache)?;
@@ -130,11 +176,12 @@ pub fn check_borrows_with_annotations(program: IrProgram, header_cache: HeaderCa
         
         // Skip checking if function is marked unsafe
         if !is_unsafe {
-            let function_errors = check_function(function)?;
-            errors.extend(function_errors);
+            let (function_errors, function_warnings) = check_function(function)?;
+            errors.extend(function_errors.iter().map(BorrowCheckError::render));
+            errors.extend(function_warnings.into_iter().map(|w| format!("warning: {}", w)));
         }
     }
-    
+
     // Run lifetime inference and validation
     for function in &program.functions {
         let inference_errors = lifetime_inference::infer_and_validate_lifetimes(function)?;
@@ -143,8 +190,9 @@ pub fn check_borrows_with_annotations(program: IrProgram, header_cache: HeaderCa
     
     // If we have header annotations, also check lifetime constraints
     if header_cache.has_signatures() {
-        let lifetime_errors = lifetime_checker::check_lifetimes_with_annotations(&program, &header_cache)?;
+        let (lifetime_errors, lifetime_warnings) = lifetime_checker::check_lifetimes_with_annotations(&program, &header_cache)?;
         errors.extend(lifetime_errors);
+        errors.extend(lifetime_warnings.into_iter().map(|w| format!("warning: {}", w)));
         
         // Also run scope-based lifetime checking
         let scope_errors = scope_lifetime::check_scoped_lifetimes(&program, &header_cache)?;
@@ -154,210 +202,603 @@ pub fn check_borrows_with_annotations(program: IrProgram, header_cache: HeaderCa
     Ok(errors)
 }
 
-fn check_function(function: &IrFunction) -> Result<Vec<String>, String> {
-    let mut errors = Vec::new();
-    let mut ownership_tracker = OwnershipTracker::new();
-    
-    // Initialize ownership for parameters and variables
+fn check_function(function: &IrFunction) -> Result<(Vec<BorrowCheckError>, Vec<String>), String> {
+    Ok(run_dataflow(function, initial_tracker_state(function)))
+}
+
+/// Seed a [`TrackerState`] from a function's declared variables, the same
+/// way for both [`check_function`]'s pass/fail walk and
+/// [`RegionAnalysis::compute`]'s region query -- the two are the same
+/// dataflow run for two different purposes, so they should start from
+/// identical initial state rather than two copies that can drift apart.
+fn initial_tracker_state(function: &IrFunction) -> TrackerState {
+    let mut initial = TrackerState::empty();
     for (name, var_info) in &function.variables {
-        ownership_tracker.set_ownership(name.clone(), var_info.ownership.clone());
-        
-        // Track reference types
+        initial.ownership.insert(name.clone(), var_info.ownership.clone());
+
+        // A parameter (no `lifetime`) lives in the function's own root
+        // scope, depth 1 -- see `OwnershipTracker::new`. A local carries
+        // its real declaration depth in `lifetime.scope_start`, set by
+        // `ir::convert_statement` as it walks the function body, so a
+        // value declared deep inside an `if`/loop is tracked as such
+        // instead of collapsing to the root scope like everything else in
+        // the flat `variables` map.
+        let declared_at = var_info.lifetime.as_ref().map(|l| l.scope_start).unwrap_or(1);
+        initial.declared_scope.insert(name.clone(), declared_at);
+
         match &var_info.ty {
             crate::ir::VariableType::Reference(_) => {
-                ownership_tracker.mark_as_reference(name.clone(), false);
+                initial.reference_info.insert(name.clone(), ReferenceInfo { is_reference: true, is_mutable: false, region: declared_at });
             }
             crate::ir::VariableType::MutableReference(_) => {
-                ownership_tracker.mark_as_reference(name.clone(), true);
+                initial.reference_info.insert(name.clone(), ReferenceInfo { is_reference: true, is_mutable: true, region: declared_at });
+            }
+            crate::ir::VariableType::Union(_) => {
+                initial.union_bases.insert(name.clone());
             }
             _ => {}
         }
     }
-    
-    // Traverse CFG and check each block
-    for node_idx in function.cfg.node_indices() {
-        let block = &function.cfg[node_idx];
-        
-        // Process statements, handling loops specially
-        let mut i = 0;
-        while i < block.statements.len() {
-            let statement = &block.statements[i];
-            
-            // Check if we're entering a loop
-            if matches!(statement, crate::ir::IrStatement::EnterLoop) {
-                // Find the matching ExitLoop
-                let mut loop_end = i + 1;
-                let mut loop_depth = 1;
-                while loop_end < block.statements.len() && loop_depth > 0 {
-                    match &block.statements[loop_end] {
-                        crate::ir::IrStatement::EnterLoop => loop_depth += 1,
-                        crate::ir::IrStatement::ExitLoop => loop_depth -= 1,
-                        _ => {}
-                    }
-                    loop_end += 1;
-                }
-                
-                // Process the loop body twice to simulate 2 iterations
-                let loop_body = &block.statements[i+1..loop_end-1];
-                
-                // First iteration
-                ownership_tracker.enter_loop();
-                
-                // Track variables declared in the loop
-                let mut loop_local_vars = HashSet::new();
-                
-                for loop_stmt in loop_body {
-                    // Track variable declarations in the loop
-                    if let crate::ir::IrStatement::Borrow { to, .. } = loop_stmt {
-                        loop_local_vars.insert(to.clone());
-                    }
-                    process_statement(loop_stmt, &mut ownership_tracker, &mut errors);
+    initial
+}
+
+/// A borrow's live range as the dataflow in [`run_dataflow`] already
+/// tracks it internally (as a [`Loan`]) -- exposed read-only so a caller
+/// doesn't have to re-derive non-lexical borrow regions itself. `kind`
+/// mirrors Rust's shared/exclusive rule: two regions only conflict if
+/// they overlap *and* at least one is [`BorrowKind::Mutable`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowRegion {
+    pub target: String,
+    pub borrower: String,
+    pub kind: BorrowKind,
+    pub created_at: usize,
+    pub last_use: usize,
+}
+
+/// Non-lexical borrow regions for a whole function, computed by running
+/// the same CFG fixpoint [`check_function`] uses and reading off the
+/// [`Loan`] records left in each block's settled exit state -- a loop's
+/// back edge widens a loan's `last_use` the same way it already does for
+/// the pass/fail checks, since both walk the identical dataflow.
+pub struct RegionAnalysis {
+    pub regions: Vec<BorrowRegion>,
+}
+
+impl RegionAnalysis {
+    pub fn compute(function: &IrFunction) -> Self {
+        let initial = initial_tracker_state(function);
+        let Some(entry_node) = function.cfg.node_indices().next() else {
+            return Self { regions: Vec::new() };
+        };
+
+        let live_out = compute_live_out(function);
+        let empty_live_out = HashSet::new();
+
+        let mut entry_states: HashMap<NodeIndex, TrackerState> = HashMap::new();
+        entry_states.insert(entry_node, initial);
+
+        let mut worklist: VecDeque<NodeIndex> = function.cfg.node_indices().collect();
+        while let Some(node) = worklist.pop_front() {
+            let entry = entry_states.entry(node).or_insert_with(TrackerState::empty).clone();
+            let node_live_out = live_out.get(&node).unwrap_or(&empty_live_out);
+            let exit = run_block(&function.cfg[node], &entry, node_live_out, &mut Vec::new());
+
+            for successor in function.cfg.neighbors(node) {
+                let joined = match entry_states.get(&successor) {
+                    Some(existing) => join_states(existing, &exit),
+                    None => exit.clone(),
+                };
+                if entry_states.get(&successor) != Some(&joined) {
+                    entry_states.insert(successor, joined);
+                    worklist.push_back(successor);
                 }
-                
-                // Save state after first iteration (but only for non-loop-local variables)
-                let state_after_first = ownership_tracker.ownership.clone();
-                
-                // Clear loop-local borrows at end of first iteration
-                ownership_tracker.clear_loop_locals(&loop_local_vars);
-                
-                // Second iteration - check for use-after-move
-                for loop_stmt in loop_body {
-                    // Before processing each statement in second iteration,
-                    // check if it would cause use-after-move (but only for non-loop-local vars)
-                    check_statement_for_loop_errors(loop_stmt, &state_after_first, &mut errors);
-                    process_statement(loop_stmt, &mut ownership_tracker, &mut errors);
+            }
+        }
+
+        let mut regions = Vec::new();
+        for node in function.cfg.node_indices() {
+            let entry = entry_states.get(&node).cloned().unwrap_or_else(TrackerState::empty);
+            let node_live_out = live_out.get(&node).unwrap_or(&empty_live_out);
+            let exit = run_block(&function.cfg[node], &entry, node_live_out, &mut Vec::new());
+            for (target, borrow_info) in &exit.borrows {
+                for (borrower, loan) in &borrow_info.borrowers {
+                    regions.push(BorrowRegion {
+                        target: target.clone(),
+                        borrower: borrower.clone(),
+                        kind: loan.kind.clone(),
+                        created_at: loan.created_at,
+                        last_use: loan.last_use,
+                    });
                 }
-                
-                // Clear loop-local borrows at end of second iteration
-                ownership_tracker.clear_loop_locals(&loop_local_vars);
-                
-                ownership_tracker.exit_loop();
-                
-                // Skip past the loop
-                i = loop_end;
-            } else {
-                // Normal statement processing
-                process_statement(statement, &mut ownership_tracker, &mut errors);
-                i += 1;
             }
         }
+        regions.sort_by(|a, b| (&a.target, a.created_at).cmp(&(&b.target, b.created_at)));
+
+        Self { regions }
     }
-    
-    Ok(errors)
 }
 
-// Helper function to check for loop-specific errors in second iteration
-fn check_statement_for_loop_errors(
-    statement: &crate::ir::IrStatement,
-    state_after_first: &HashMap<String, OwnershipState>,
-    errors: &mut Vec<String>,
-) {
-    match statement {
-        crate::ir::IrStatement::Move { from, .. } => {
-            if let Some(state) = state_after_first.get(from) {
-                if *state == OwnershipState::Moved {
-                    errors.push(format!(
-                        "Use after move in loop: variable '{}' was moved in first iteration and used again in second iteration",
-                        from
-                    ));
-                }
+/// Monotone forward dataflow over `function.cfg`: every node's entry state
+/// starts empty except the entry block's (seeded with the function's
+/// parameter/variable ownership), then a worklist repeatedly pops a block,
+/// runs it from its current entry state, and joins the resulting exit
+/// state into every successor's entry state, re-enqueuing a successor
+/// whenever that join actually changes it. A loop's back edge feeds its
+/// own join just like any other edge, so the fixed point accounts for any
+/// number of iterations instead of a hard-coded two. Violations are
+/// reported once, by a final pass over the settled entry states.
+fn run_dataflow(function: &IrFunction, initial: TrackerState) -> (Vec<BorrowCheckError>, Vec<String>) {
+    let Some(entry_node) = function.cfg.node_indices().next() else {
+        return (Vec::new(), Vec::new());
+    };
+
+    let live_out = compute_live_out(function);
+    let empty_live_out = HashSet::new();
+
+    let mut entry_states: HashMap<NodeIndex, TrackerState> = HashMap::new();
+    entry_states.insert(entry_node, initial);
+
+    let mut worklist: VecDeque<NodeIndex> = function.cfg.node_indices().collect();
+    while let Some(node) = worklist.pop_front() {
+        let entry = entry_states.entry(node).or_insert_with(TrackerState::empty).clone();
+        let node_live_out = live_out.get(&node).unwrap_or(&empty_live_out);
+        let exit = run_block(&function.cfg[node], &entry, node_live_out, &mut Vec::new());
+
+        for successor in function.cfg.neighbors(node) {
+            let joined = match entry_states.get(&successor) {
+                Some(existing) => join_states(existing, &exit),
+                None => exit.clone(),
+            };
+            if entry_states.get(&successor) != Some(&joined) {
+                entry_states.insert(successor, joined);
+                worklist.push_back(successor);
             }
         }
-        crate::ir::IrStatement::Assign { rhs, .. } => {
-            if let crate::ir::IrExpression::Variable(var) = rhs {
-                if let Some(state) = state_after_first.get(var) {
-                    if *state == OwnershipState::Moved {
-                        errors.push(format!(
-                            "Use after move in loop: variable '{}' was moved in first iteration and used again in second iteration",
-                            var
-                        ));
-                    }
-                }
+    }
+
+    // Final reporting pass over the now-settled entry states, so a block
+    // visited repeatedly while the fixpoint was still converging doesn't
+    // report the same violation once per visit. Each block's exit state's
+    // `used_mut` is unioned across the whole function -- mirrors rustc's
+    // `used_mut_nodes`: a mutable reference only earns the "unused mut"
+    // warning below if no block, on any path, ever wrote through it.
+    let mut errors = Vec::new();
+    let mut used_mut: HashSet<String> = HashSet::new();
+    let mut reservation_warnings: HashSet<String> = HashSet::new();
+    let mut unchecked_loans: HashSet<String> = HashSet::new();
+    for node in function.cfg.node_indices() {
+        let entry = entry_states.get(&node).cloned().unwrap_or_else(TrackerState::empty);
+        let node_live_out = live_out.get(&node).unwrap_or(&empty_live_out);
+        let exit = run_block(&function.cfg[node], &entry, node_live_out, &mut errors);
+        used_mut.extend(exit.used_mut);
+        reservation_warnings.extend(exit.reservation_warnings);
+        unchecked_loans.extend(exit.unchecked_loans);
+    }
+
+    let mut warnings: Vec<String> = function
+        .variables
+        .iter()
+        .filter(|(name, var_info)| {
+            matches!(var_info.ty, crate::ir::VariableType::MutableReference(_)) && !used_mut.contains(name.as_str())
+        })
+        .map(|(name, _)| format!("unnecessary `&mut`: '{}' is declared as a mutable reference but never mutated through it", name))
+        .collect();
+    warnings.extend(reservation_warnings);
+    warnings.extend(unchecked_loans);
+
+    (errors, warnings)
+}
+
+/// Run one basic block's statements from `entry`, collecting any
+/// violations they report into `errors`, and return the resulting exit
+/// state. `live_out` is this block's cross-block liveness (see
+/// `compute_live_out`) -- a name in it is read by some successor, so
+/// `compute_last_uses` must not let the absence of a later read *within
+/// this block* convince it the borrow has already died.
+fn run_block(
+    block: &crate::ir::BasicBlock,
+    entry: &TrackerState,
+    live_out: &HashSet<String>,
+    errors: &mut Vec<BorrowCheckError>,
+) -> TrackerState {
+    let mut tracker = OwnershipTracker::from_state(entry);
+    let last_uses = compute_last_uses(&block.statements, live_out);
+    for (index, statement) in block.statements.iter().enumerate() {
+        process_statement(statement, index, &block.statements, &last_uses, &mut tracker, errors);
+    }
+    tracker.clone_state()
+}
+
+/// Join two dataflow states the way a shared successor sees its
+/// predecessors: a place is `Moved` only if every incoming path left it
+/// `Moved`, `MaybeMoved` if some but not all did, and the active borrow
+/// set is the union of both paths' -- a borrow visible from either
+/// predecessor still constrains what the successor can do, since control
+/// might have arrived via the path that created it.
+fn join_states(a: &TrackerState, b: &TrackerState) -> TrackerState {
+    let mut ownership = HashMap::new();
+    for var in a.ownership.keys().chain(b.ownership.keys()).collect::<HashSet<_>>() {
+        let left = a.ownership.get(var).cloned().unwrap_or(OwnershipState::Owned);
+        let right = b.ownership.get(var).cloned().unwrap_or(OwnershipState::Owned);
+        ownership.insert(var.clone(), join_ownership(&left, &right));
+    }
+
+    let mut borrows = a.borrows.clone();
+    for (var, info) in &b.borrows {
+        let entry = borrows.entry(var.clone()).or_default();
+        for (name, borrower) in &info.borrowers {
+            entry.borrowers.entry(name.clone()).or_insert_with(|| borrower.clone());
+        }
+        entry.recompute_counts();
+    }
+
+    let mut reference_info = a.reference_info.clone();
+    reference_info.extend(b.reference_info.clone());
+
+    let mut used_mut = a.used_mut.clone();
+    used_mut.extend(b.used_mut.clone());
+
+    // A place's declared scope is set once and never changes, so the two
+    // sides should agree wherever both have an entry; take the shallower
+    // (more conservative) depth on the rare disagreement rather than
+    // assume either side is authoritative.
+    let mut declared_scope = a.declared_scope.clone();
+    for (var, &depth) in &b.declared_scope {
+        declared_scope
+            .entry(var.clone())
+            .and_modify(|existing| *existing = (*existing).min(depth))
+            .or_insert(depth);
+    }
+
+    let mut reservation_warnings = a.reservation_warnings.clone();
+    reservation_warnings.extend(b.reservation_warnings.clone());
+
+    let mut union_bases = a.union_bases.clone();
+    union_bases.extend(b.union_bases.clone());
+
+    let mut unchecked_loans = a.unchecked_loans.clone();
+    unchecked_loans.extend(b.unchecked_loans.clone());
+
+    TrackerState { ownership, borrows, reference_info, used_mut, declared_scope, reservation_warnings, union_bases, unchecked_loans }
+}
+
+/// Join two ownership states reaching the same program point from
+/// different edges: `Moved` only survives if both sides agree, anything
+/// that disagrees on whether a move happened collapses to `MaybeMoved`
+/// (already-`MaybeMoved` is absorbing, since "moved on some path" stays
+/// true no matter what the other path did), and two sides that agree on
+/// anything else just keep that value.
+fn join_ownership(a: &OwnershipState, b: &OwnershipState) -> OwnershipState {
+    match (a, b) {
+        (OwnershipState::Moved, OwnershipState::Moved) => OwnershipState::Moved,
+        (OwnershipState::Moved, _) | (_, OwnershipState::Moved) => OwnershipState::MaybeMoved,
+        (OwnershipState::MaybeMoved, _) | (_, OwnershipState::MaybeMoved) => OwnershipState::MaybeMoved,
+        _ => a.clone(),
+    }
+}
+
+/// First pass over a statement sequence: for every variable, the highest
+/// index at which it appears as an operand (a borrow/move source, a call
+/// argument, an assignment's rhs, or a returned/read value). A variable
+/// never used again after the statement that creates it gets
+/// `last_use == creation_index`, so its borrow has already expired by the
+/// time the very next statement runs.
+///
+/// `live_out` (from `compute_live_out`) overrides that for a name a
+/// successor block still reads: this statement list's own index space has
+/// nothing left to say about when such a name actually dies, so it's
+/// pinned open (`usize::MAX`) rather than pruned here just because it
+/// happens not to be reread before the block ends. This is what makes
+/// borrow expiry non-lexical across the whole CFG instead of only within
+/// whichever block created the borrow -- `EnterScope`/`ExitScope` remain
+/// the outer bound, but a loan that's still live past its creating block
+/// no longer expires early just because that block ends.
+fn compute_last_uses(statements: &[crate::ir::IrStatement], live_out: &HashSet<String>) -> HashMap<String, usize> {
+    let mut last_use = HashMap::new();
+    for (index, stmt) in statements.iter().enumerate() {
+        for operand in operands_of(stmt) {
+            last_use.insert(operand, index);
+        }
+    }
+    for name in live_out {
+        last_use.insert(name.clone(), usize::MAX);
+    }
+    last_use
+}
+
+/// Backward liveness over `function.cfg`, with no def/kill step: for each
+/// block, every name read anywhere reachable from its exit. Coarser than a
+/// textbook live-variables pass -- a name reread only on some unrelated
+/// path can make it look live here too -- but that's the safe direction
+/// for the one question this feeds into (`compute_last_uses`): "might a
+/// successor still read this, so don't expire it here," never the other
+/// way round.
+fn compute_live_out(function: &IrFunction) -> HashMap<NodeIndex, HashSet<String>> {
+    let uses: HashMap<NodeIndex, HashSet<String>> = function
+        .cfg
+        .node_indices()
+        .map(|node| (node, function.cfg[node].statements.iter().flat_map(operands_of).collect()))
+        .collect();
+
+    let mut live_in: HashMap<NodeIndex, HashSet<String>> =
+        function.cfg.node_indices().map(|node| (node, HashSet::new())).collect();
+
+    let mut worklist: VecDeque<NodeIndex> = function.cfg.node_indices().collect();
+    while let Some(node) = worklist.pop_front() {
+        let mut live_out = HashSet::new();
+        for successor in function.cfg.neighbors_directed(node, Direction::Outgoing) {
+            live_out.extend(live_in[&successor].iter().cloned());
+        }
+
+        let mut new_live_in = live_out;
+        new_live_in.extend(uses[&node].iter().cloned());
+
+        if new_live_in != live_in[&node] {
+            live_in.insert(node, new_live_in);
+            for predecessor in function.cfg.neighbors_directed(node, Direction::Incoming) {
+                worklist.push_back(predecessor);
             }
         }
-        _ => {}
     }
+
+    function
+        .cfg
+        .node_indices()
+        .map(|node| {
+            let mut live_out = HashSet::new();
+            for successor in function.cfg.neighbors_directed(node, Direction::Outgoing) {
+                live_out.extend(live_in[&successor].iter().cloned());
+            }
+            (node, live_out)
+        })
+        .collect()
+}
+
+/// Names a statement reads (as opposed to declares): the source of a
+/// borrow or move, call arguments, an assignment's variable rhs, a
+/// returned value, an explicit drop, or a bare `Read`.
+fn operands_of(stmt: &crate::ir::IrStatement) -> Vec<String> {
+    match stmt {
+        crate::ir::IrStatement::Assign { rhs, .. } => match rhs {
+            crate::ir::IrExpression::Variable(name)
+            | crate::ir::IrExpression::Move(name)
+            | crate::ir::IrExpression::Borrow(name, _) => vec![name.clone()],
+            crate::ir::IrExpression::New(_) => vec![],
+        },
+        crate::ir::IrStatement::Move { from, .. } => vec![from.clone()],
+        crate::ir::IrStatement::Borrow { from, .. } => vec![from.clone()],
+        crate::ir::IrStatement::CallExpr { args, .. } => args.clone(),
+        crate::ir::IrStatement::Return { value: Some(value) } => vec![value.clone()],
+        crate::ir::IrStatement::Drop(name) => vec![name.clone()],
+        crate::ir::IrStatement::Read(names) => names.clone(),
+        crate::ir::IrStatement::Activate { reference } => vec![reference.clone()],
+        _ => vec![],
+    }
+}
+
+/// Forward search (rustc's `find_use`) for the first statement after
+/// `from` that actually reads `place`, so a borrow conflict's explanation
+/// can point at a real later use instead of just asserting the existing
+/// borrow is still live.
+fn find_later_use(statements: &[crate::ir::IrStatement], from: usize, place: &str) -> Option<usize> {
+    statements
+        .iter()
+        .enumerate()
+        .skip(from + 1)
+        .find(|(_, stmt)| operands_of(stmt).iter().any(|operand| operand == place))
+        .map(|(index, _)| index)
+}
+
+/// The secondary ("'r' was borrowed at statement N", "borrow later used
+/// here") spans explaining why the still-live borrow of `kind` is blocking
+/// a new one -- modeled on rustc's `explain_borrow`: walk forward from the
+/// blocking borrow's creation point for the next statement that reads it,
+/// and if none turns up before the block ends, say so instead of implying
+/// one exists. Empty if no borrower of exactly that kind is on record --
+/// e.g. when the only shared loan counted in `immutable_count` is a
+/// still-reserved `TwoPhaseMutable` one.
+fn explain_borrow_conflict(
+    statements: &[crate::ir::IrStatement],
+    current_borrows: &BorrowInfo,
+    kind: BorrowKind,
+) -> Vec<(String, String)> {
+    let Some((name, created_at, _)) = current_borrows.earliest_borrower_of_kind(kind) else {
+        return Vec::new();
+    };
+
+    let created_here = (
+        format!("'{}' was borrowed at statement {}", name, created_at),
+        format!("statement {}", created_at),
+    );
+    let later_use = match find_later_use(statements, created_at, name) {
+        Some(use_at) => (
+            "borrow later used here".to_string(),
+            format!("statement {}", use_at),
+        ),
+        None => (
+            format!("'{}' is never used again before the end of this block", name),
+            format!("statement {} (scope end)", created_at),
+        ),
+    };
+
+    vec![created_here, later_use]
 }
 
 // Extract statement processing logic into a separate function
 fn process_statement(
     statement: &crate::ir::IrStatement,
+    index: usize,
+    statements: &[crate::ir::IrStatement],
+    last_uses: &HashMap<String, usize>,
     ownership_tracker: &mut OwnershipTracker,
-    errors: &mut Vec<String>,
+    errors: &mut Vec<BorrowCheckError>,
 ) {
     match statement {
         crate::ir::IrStatement::Move { from, to } => {
             eprintln!("DEBUG ANALYSIS: Processing Move from '{}' to '{}'", from, to);
-            // Skip checks if we're in an unsafe block
-            if ownership_tracker.is_in_unsafe_block() {
-                // Still update ownership state for consistency
-                ownership_tracker.set_ownership(from.clone(), OwnershipState::Moved);
-                ownership_tracker.set_ownership(to.clone(), OwnershipState::Owned);
-                return;
-            }
-            
-            // Check if 'from' is owned and not moved
-            let from_state = ownership_tracker.get_ownership(from);
-            eprintln!("DEBUG ANALYSIS: '{}' state: {:?}", from, from_state);
-            
+            // Unlike the aliasing checks below, move tracking stays on
+            // inside an unsafe region -- `EnterUnsafe` suspends proving
+            // borrows don't overlap, not use-after-move, which is a
+            // soundness/lifetime issue rather than an aliasing one.
+
             // Can't move from a reference
             if ownership_tracker.is_reference(from) {
-                errors.push(format!(
-                    "Cannot move out of '{}' because it is behind a reference",
-                    from
+                errors.push(BorrowCheckError::new(
+                    ErrorKind::MoveThroughReference,
+                    index,
+                    format!("Cannot move out of '{}' because it is behind a reference", from),
                 ));
                 return;
             }
-            
-            if from_state == Some(&OwnershipState::Moved) {
-                errors.push(format!(
-                    "Use after move: variable '{}' has already been moved",
-                    from
-                ));
+
+            // Check if 'from' -- or an overlapping sub-path of it -- is
+            // still owned and not already (partially) moved.
+            match ownership_tracker.check_place(from) {
+                MoveCheckResult::Moved => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Use after move: variable '{}' has already been moved", from),
+                    ));
+                }
+                MoveCheckResult::MaybeMoved => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!(
+                            "Use of possibly-moved variable '{}': moved on some but not all paths reaching this point",
+                            from
+                        ),
+                    ).with_secondary(vec![(
+                        format!("'{}' was moved on only one arm of a prior if/else", from),
+                        "branch merge".to_string(),
+                    )]));
+                }
+                MoveCheckResult::MovedViaParent { parent } => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Use after move: '{}' was already moved as part of '{}'", from, parent),
+                    ));
+                }
+                MoveCheckResult::PartiallyMoved { child } => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Cannot move out of '{}' because '{}' was already moved out of it", from, child),
+                    ));
+                }
+                MoveCheckResult::Ok => {}
             }
-            
+
             // Handle temporary move markers (from std::move in function calls)
             if to.starts_with("_temp_move_") || to.starts_with("_moved_") {
                 // Just mark the source as moved, don't create the temporary
-                ownership_tracker.set_ownership(from.clone(), OwnershipState::Moved);
+                ownership_tracker.mark_place_moved(from);
             } else {
                 // Transfer ownership for regular moves
-                ownership_tracker.set_ownership(from.clone(), OwnershipState::Moved);
+                ownership_tracker.mark_place_moved(from);
                 ownership_tracker.set_ownership(to.clone(), OwnershipState::Owned);
+                ownership_tracker.record_declaration(to);
             }
         }
-        
+
         crate::ir::IrStatement::Borrow { from, to, kind } => {
-            // Skip checks if we're in an unsafe block
-            if ownership_tracker.is_in_unsafe_block() {
-                // Still record the borrow for consistency
-                ownership_tracker.add_borrow(from.clone(), to.clone(), kind.clone());
-                ownership_tracker.mark_as_reference(to.clone(), *kind == BorrowKind::Mutable);
-                return;
+            // `to`'s non-lexical extent: the last statement (in this
+            // sequence) that actually uses it, or its own creation point
+            // if it's never read again.
+            let last_use = last_uses.get(to).copied().unwrap_or(index);
+
+            // The region this borrow hands `to`: the scope depth `from`
+            // itself lives in, not the scope the `Borrow` statement
+            // happens to execute in -- borrowing a function parameter
+            // from three blocks deep is fine precisely because the
+            // parameter's region is 1 regardless of how deep the borrow
+            // site is.
+            let region = ownership_tracker.region_of(from);
+
+            // Check if the source -- or an overlapping sub-path of it --
+            // is accessible. This runs even inside an unsafe region:
+            // use-after-move is a soundness/lifetime issue, not the
+            // aliasing one `EnterUnsafe` exists to suspend.
+            match ownership_tracker.check_place(from) {
+                MoveCheckResult::Moved => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Cannot borrow '{}' because it has been moved", from),
+                    ));
+                    return;
+                }
+                MoveCheckResult::MaybeMoved => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!(
+                            "Cannot borrow '{}': possibly moved on some but not all paths reaching this point",
+                            from
+                        ),
+                    ).with_secondary(vec![(
+                        format!("'{}' was moved on only one arm of a prior if/else", from),
+                        "branch merge".to_string(),
+                    )]));
+                    return;
+                }
+                MoveCheckResult::MovedViaParent { parent } => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Cannot borrow '{}' because '{}' was already moved as a whole", from, parent),
+                    ));
+                    return;
+                }
+                MoveCheckResult::PartiallyMoved { child } => {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::UseAfterMove,
+                        index,
+                        format!("Cannot borrow '{}' because '{}' was already moved out of it", from, child),
+                    ));
+                    return;
+                }
+                MoveCheckResult::Ok => {}
             }
-            
-            // Check if the source is accessible
-            let from_state = ownership_tracker.get_ownership(from);
-            
-            if from_state == Some(&OwnershipState::Moved) {
-                errors.push(format!(
-                    "Cannot borrow '{}' because it has been moved",
-                    from
-                ));
+
+            // Drop every active borrow whose last use already fell behind
+            // `index` before checking for a conflict -- non-lexical
+            // lifetimes instead of "alive until the closing brace".
+            ownership_tracker.expire_borrows(index);
+
+            // Inside an unsafe region, skip the aliasing-overlap checks
+            // below entirely -- this is the escape hatch for code the
+            // original C++ deliberately aliases (e.g. parallel writes to
+            // disjoint array indices the checker can't prove disjoint) --
+            // but still record the loan as unchecked so a summary can
+            // list exactly where manual reasoning was relied upon.
+            if ownership_tracker.is_in_unsafe_block() {
+                ownership_tracker.record_unchecked_loan(from, to, index);
+                ownership_tracker.add_borrow(from.clone(), to.clone(), kind.clone(), index, last_use);
+                ownership_tracker.record_declaration(to);
+                ownership_tracker.mark_as_reference(to.clone(), *kind == BorrowKind::Mutable, region);
                 return;
             }
-            
-            // Check existing borrows
-            let current_borrows = ownership_tracker.get_borrows(from);
-            
+
+            // Check existing borrows, including ones on an ancestor or
+            // descendant path -- a borrow of `s.a` and one of `s.b` are
+            // disjoint and don't conflict, but a borrow of `s` conflicts
+            // with a borrow of any of its fields.
+            let current_borrows = ownership_tracker.get_overlapping_borrows(from);
+
             match kind {
                 BorrowKind::Immutable => {
                     // Can have multiple immutable borrows, but not if there's a mutable borrow
                     if current_borrows.has_mutable {
-                        errors.push(format!(
-                            "Cannot create immutable reference to '{}': already mutably borrowed",
-                            from
-                        ));
+                        let secondary = explain_borrow_conflict(statements, &current_borrows, BorrowKind::Mutable);
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::DoubleBorrow,
+                            index,
+                            format!("Cannot create immutable reference to '{}': already mutably borrowed", from),
+                        ).with_secondary(secondary));
                     }
                     // In C++, const references are allowed even when the value is being modified
                     // through another path, but we enforce Rust's stricter rules
@@ -365,63 +806,165 @@ fn process_statement(
                 BorrowKind::Mutable => {
                     // Can only have one mutable borrow, and no immutable borrows
                     if current_borrows.immutable_count > 0 {
-                        errors.push(format!(
-                            "Cannot create mutable reference to '{}': already immutably borrowed",
-                            from
-                        ));
+                        let secondary = explain_borrow_conflict(statements, &current_borrows, BorrowKind::Immutable);
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::MutableBorrowWhileImmutable,
+                            index,
+                            format!("Cannot create mutable reference to '{}': already immutably borrowed", from),
+                        ).with_secondary(secondary));
                     } else if current_borrows.has_mutable {
-                        errors.push(format!(
-                            "Cannot create mutable reference to '{}': already mutably borrowed",
-                            from
+                        let secondary = explain_borrow_conflict(statements, &current_borrows, BorrowKind::Mutable);
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::DoubleBorrow,
+                            index,
+                            format!("Cannot create mutable reference to '{}': already mutably borrowed", from),
+                        ).with_secondary(secondary));
+                    }
+                }
+                BorrowKind::TwoPhaseMutable => {
+                    // Reserved, not yet activated: it behaves like a
+                    // shared borrow for now, so an existing mutable loan
+                    // still conflicts outright, but an existing shared
+                    // borrow only earns a non-fatal `reservation_conflict`
+                    // -- the compiler's own conservative lint for this
+                    // case, since whether it's really a problem depends on
+                    // what runs before the eventual `Activate`.
+                    if current_borrows.has_mutable {
+                        let secondary = explain_borrow_conflict(statements, &current_borrows, BorrowKind::Mutable);
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::DoubleBorrow,
+                            index,
+                            format!("Cannot reserve mutable reference to '{}': already mutably borrowed", from),
+                        ).with_secondary(secondary));
+                    } else if current_borrows.immutable_count > 0 {
+                        ownership_tracker.record_reservation_conflict(format!(
+                            "reservation_conflict: two-phase mutable borrow of '{}' reserved at statement {} while a shared borrow is still active",
+                            from, index
                         ));
                     }
                 }
             }
-            
+
+            // Re-borrowing mutably through an existing mutable reference
+            // is itself a use of that reference's mutability.
+            if *kind == BorrowKind::Mutable && ownership_tracker.is_mutable_reference(from) {
+                ownership_tracker.mark_used_mut(from.clone());
+            }
+
             // Record the borrow
-            ownership_tracker.add_borrow(from.clone(), to.clone(), kind.clone());
-            ownership_tracker.mark_as_reference(to.clone(), *kind == BorrowKind::Mutable);
+            ownership_tracker.add_borrow(from.clone(), to.clone(), kind.clone(), index, last_use);
+            ownership_tracker.record_declaration(to);
+            ownership_tracker.mark_as_reference(to.clone(), *kind == BorrowKind::Mutable, region);
         }
-        
-        crate::ir::IrStatement::Assign { lhs, rhs } => {
+
+        crate::ir::IrStatement::Activate { reference } => {
             // Skip checks if we're in an unsafe block
             if ownership_tracker.is_in_unsafe_block() {
                 return;
             }
-            
-            // Check if we're trying to modify through a const reference
-            if ownership_tracker.is_reference(lhs) && !ownership_tracker.is_mutable_reference(lhs) {
-                errors.push(format!(
-                    "Cannot assign to '{}' through const reference",
-                    lhs
-                ));
+            ownership_tracker.activate_two_phase_borrow(reference, index, statements, errors);
+        }
+
+        crate::ir::IrStatement::Assign { lhs, rhs } => {
+            // Writing through a const reference is an aliasing/exclusivity
+            // rule, same family as the conflict checks an unsafe region
+            // suspends -- skip it there, but (below) still track moves,
+            // since that's not what the escape hatch is for.
+            if !ownership_tracker.is_in_unsafe_block() {
+                // Check if we're trying to modify through a const reference
+                if ownership_tracker.is_reference(lhs) && !ownership_tracker.is_mutable_reference(lhs) {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::ConstReferenceAssignment,
+                        index,
+                        format!("Cannot assign to '{}' through const reference", lhs),
+                    ));
+                } else if ownership_tracker.is_mutable_reference(lhs) {
+                    // A write that actually went through, as opposed to the
+                    // const-reference violation above -- this is what earns
+                    // the mutable reference its keep.
+                    ownership_tracker.mark_used_mut(lhs.clone());
+                }
             }
-            
-            // Check if the rhs uses a moved variable
+
+            // Check if the rhs uses a moved place
             if let crate::ir::IrExpression::Variable(rhs_var) = rhs {
-                if ownership_tracker.get_ownership(rhs_var) == Some(&OwnershipState::Moved) {
-                    errors.push(format!(
-                        "Use after move: variable '{}' has been moved",
-                        rhs_var
-                    ));
+                match ownership_tracker.check_place(rhs_var) {
+                    MoveCheckResult::Moved => {
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::UseAfterMove,
+                            index,
+                            format!("Use after move: variable '{}' has been moved", rhs_var),
+                        ));
+                    }
+                    MoveCheckResult::MaybeMoved => {
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::UseAfterMove,
+                            index,
+                            format!(
+                                "Use of possibly-moved variable '{}': moved on some but not all paths reaching this point",
+                                rhs_var
+                            ),
+                        ).with_secondary(vec![(
+                            format!("'{}' was moved on only one arm of a prior if/else", rhs_var),
+                            "branch merge".to_string(),
+                        )]));
+                    }
+                    MoveCheckResult::MovedViaParent { parent } => {
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::UseAfterMove,
+                            index,
+                            format!("Use after move: '{}' was already moved as part of '{}'", rhs_var, parent),
+                        ));
+                    }
+                    MoveCheckResult::PartiallyMoved { child } => {
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::UseAfterMove,
+                            index,
+                            format!("Cannot use '{}' because '{}' was already moved out of it", rhs_var, child),
+                        ));
+                    }
+                    MoveCheckResult::Ok => {}
                 }
             }
+
+            ownership_tracker.record_declaration(lhs);
         }
-        
+
+        crate::ir::IrStatement::Return { value } => {
+            // A reference whose region is deeper than the function's own
+            // root scope (depth 1) points at something local to this
+            // function -- which is about to be torn down on return, same
+            // as every other local.
+            if let Some(name) = value {
+                if let Some(region) = ownership_tracker.reference_region(name) {
+                    if region > 1 {
+                        errors.push(BorrowCheckError::new(
+                            ErrorKind::DanglingReference,
+                            index,
+                            format!(
+                                "Cannot return '{}': it references a value local to this function, which does not live past the call",
+                                name
+                            ),
+                        ));
+                    }
+                }
+            }
+        }
+
         crate::ir::IrStatement::EnterScope => {
             ownership_tracker.enter_scope();
         }
-        
+
         crate::ir::IrStatement::ExitScope => {
-            ownership_tracker.exit_scope();
+            ownership_tracker.exit_scope(index, errors);
         }
         
-        crate::ir::IrStatement::EnterLoop => {
-            // Handled at the higher level
-        }
-        
-        crate::ir::IrStatement::ExitLoop => {
-            // Handled at the higher level
+        crate::ir::IrStatement::EnterLoop | crate::ir::IrStatement::ExitLoop => {
+            // `ir::convert_function` now lowers a loop's body into its own
+            // CFG blocks with a real back edge (see `ir::lower_statements`),
+            // so these markers no longer appear inline in a block's
+            // statement list. Left as a no-op for any hand-built IR (e.g.
+            // tests) that still pushes one directly.
         }
         
         crate::ir::IrStatement::EnterUnsafe => {
@@ -435,26 +978,29 @@ fn process_statement(
         }
         
         crate::ir::IrStatement::If { then_branch, else_branch } => {
-            // Skip checking if we're in an unsafe block
-            if ownership_tracker.is_in_unsafe_block() {
-                return;
-            }
+            // Still walk both branches inside an unsafe region -- each
+            // nested statement applies its own unsafe-aware skip (move
+            // tracking stays on; only the aliasing checks are suspended),
+            // so short-circuiting the whole branch here would drop move
+            // tracking for everything nested inside it.
             // Handle conditional execution with path-sensitive analysis
             // Save current state before branching
             let state_before_if = ownership_tracker.clone_state();
-            
+
             // Process then branch
-            for stmt in then_branch {
-                process_statement(stmt, ownership_tracker, errors);
+            let then_last_uses = compute_last_uses(then_branch, &HashSet::new());
+            for (local_index, stmt) in then_branch.iter().enumerate() {
+                process_statement(stmt, local_index, then_branch, &then_last_uses, ownership_tracker, errors);
             }
             let state_after_then = ownership_tracker.clone_state();
-            
+
             // Restore state and process else branch if it exists
             ownership_tracker.restore_state(&state_before_if);
-            
+
             if let Some(else_stmts) = else_branch {
-                for stmt in else_stmts {
-                    process_statement(stmt, ownership_tracker, errors);
+                let else_last_uses = compute_last_uses(else_stmts, &HashSet::new());
+                for (local_index, stmt) in else_stmts.iter().enumerate() {
+                    process_statement(stmt, local_index, else_stmts, &else_last_uses, ownership_tracker, errors);
                 }
                 let state_after_else = ownership_tracker.clone_state();
                 
@@ -477,25 +1023,55 @@ struct OwnershipTracker {
     reference_info: HashMap<String, ReferenceInfo>,
     // Stack of scopes, each scope tracks borrows created in it
     scope_stack: Vec<ScopeInfo>,
-    // Loop tracking
-    loop_depth: usize,
-    // Save state when entering a loop (for 2nd iteration checking)
-    loop_entry_states: Vec<LoopEntryState>,
     // Track if we're in an unsafe block
     unsafe_depth: usize,
+    // Places written through as a mutable reference, or re-borrowed
+    // mutably -- mirrors rustc's `used_mut_nodes` so a `MutableReference`
+    // that never earns its keep can be flagged after the function settles.
+    used_mut: HashSet<String>,
+    // The scope depth each place was first observed at -- a loan/region
+    // layer on top of the plain ownership map, so a reference can be
+    // checked against the depth of whatever it currently points to.
+    declared_scope: HashMap<String, usize>,
+    // Non-fatal `reservation_conflict` messages, one per two-phase
+    // mutable borrow reserved while a shared borrow of the same place was
+    // still active -- mirrors `used_mut`'s "collect now, report once the
+    // function settles" shape rather than pushing straight into `errors`.
+    reservation_warnings: HashSet<String>,
+    // Base variable names declared as a C++ `union` -- set once from
+    // `function.variables` and never changed afterwards, same as
+    // `declared_scope`. Any two place paths sharing one of these bases
+    // overlap regardless of which fields they project, since a union's
+    // fields all alias the same storage.
+    union_bases: HashSet<String>,
+    // Loans accepted without an aliasing check because they were created
+    // inside an `EnterUnsafe`/`ExitUnsafe` region -- mirrors
+    // `reservation_warnings`'s "collect now, report once the function
+    // settles" shape, so a summary can list exactly where the checker
+    // relied on manual reasoning instead of proving exclusivity itself.
+    unchecked_loans: HashSet<String>,
 }
 
-#[derive(Clone)]
+/// A basic block's ownership/borrow/reference snapshot at a single point
+/// in the CFG -- what [`run_dataflow`] carries between blocks along edges,
+/// and what [`join_states`] combines at a block with more than one
+/// predecessor.
+#[derive(Clone, PartialEq, Default)]
 struct TrackerState {
     ownership: HashMap<String, OwnershipState>,
     borrows: HashMap<String, BorrowInfo>,
     reference_info: HashMap<String, ReferenceInfo>,
+    used_mut: HashSet<String>,
+    declared_scope: HashMap<String, usize>,
+    reservation_warnings: HashSet<String>,
+    union_bases: HashSet<String>,
+    unchecked_loans: HashSet<String>,
 }
 
-#[derive(Clone)]
-struct LoopEntryState {
-    ownership: HashMap<String, OwnershipState>,
-    borrows: HashMap<String, BorrowInfo>,
+impl TrackerState {
+    fn empty() -> Self {
+        Self::default()
+    }
 }
 
 #[derive(Default, Clone)]
@@ -504,17 +1080,66 @@ struct ScopeInfo {
     local_borrows: HashSet<String>,
 }
 
-#[derive(Default, Clone)]
+/// A single gathered loan: a borrow of some place, recorded with enough
+/// information to later explain a conflict against it -- its kind, where
+/// it was created, and the last statement that still needs it alive.
+/// This is deliberately a miniature of rustc's own loan record, gathered
+/// as each `Borrow` statement is processed rather than in one upfront
+/// pass over the whole function: the checker is already a single forward
+/// walk per block, so a loan only needs to exist from its creation point
+/// to wherever `expire_borrows` or a scope exit retires it.
+#[derive(Debug, Clone, PartialEq)]
+struct Loan {
+    kind: BorrowKind,
+    created_at: usize,
+    last_use: usize,
+}
+
+#[derive(Default, Clone, PartialEq)]
 struct BorrowInfo {
     immutable_count: usize,
     has_mutable: bool,
-    borrowers: HashSet<String>,
+    // Borrower name -> the loan it holds, so a borrower can be expired
+    // once its last use has passed and a conflict can point back at
+    // exactly which borrow -- and where -- is still live.
+    borrowers: HashMap<String, Loan>,
 }
 
-#[derive(Clone)]
+impl BorrowInfo {
+    /// A still-reserved `TwoPhaseMutable` loan counts as immutable, not
+    /// mutable -- that's the whole point of the reservation phase: other
+    /// shared reads of the same place are fine right up until an
+    /// `Activate` flips its entry to plain `Mutable`, at which point it
+    /// falls out of this count and into `has_mutable` below like any
+    /// other exclusive loan.
+    fn recompute_counts(&mut self) {
+        self.immutable_count = self.borrowers.values()
+            .filter(|loan| matches!(loan.kind, BorrowKind::Immutable | BorrowKind::TwoPhaseMutable))
+            .count();
+        self.has_mutable = self.borrowers.values().any(|loan| loan.kind == BorrowKind::Mutable);
+    }
+
+    /// The earliest-created borrower of the given kind, i.e. the one a
+    /// conflicting borrow should blame -- the original still-live
+    /// reference, not whichever one happens to iterate first.
+    fn earliest_borrower_of_kind(&self, kind: BorrowKind) -> Option<(&str, usize, usize)> {
+        self.borrowers
+            .iter()
+            .filter(|(_, loan)| loan.kind == kind)
+            .min_by_key(|(_, loan)| loan.created_at)
+            .map(|(name, loan)| (name.as_str(), loan.created_at, loan.last_use))
+    }
+}
+
+#[derive(Clone, PartialEq)]
 struct ReferenceInfo {
     is_reference: bool,
     is_mutable: bool,
+    /// The scope depth of the value this reference currently points to --
+    /// its loan's region. A reference whose region is deeper than its own
+    /// declared scope (or than the function's root scope, at `return`)
+    /// points at something that won't outlive it: a dangling reference.
+    region: usize,
 }
 
 impl OwnershipTracker {
@@ -524,15 +1149,28 @@ impl OwnershipTracker {
             borrows: HashMap::new(),
             reference_info: HashMap::new(),
             scope_stack: Vec::new(),
-            loop_depth: 0,
-            loop_entry_states: Vec::new(),
             unsafe_depth: 0,
+            used_mut: HashSet::new(),
+            declared_scope: HashMap::new(),
+            reservation_warnings: HashSet::new(),
+            union_bases: HashSet::new(),
+            unchecked_loans: HashSet::new(),
         };
         // Start with a root scope
         tracker.scope_stack.push(ScopeInfo::default());
         tracker
     }
-    
+
+    /// A fresh tracker seeded from a dataflow block's entry state -- the
+    /// scope stack (lexical, not part of the dataflow lattice) and unsafe
+    /// depth always start clean, since `EnterScope`/`EnterUnsafe` markers
+    /// are emitted and consumed within the same block they open in.
+    fn from_state(state: &TrackerState) -> Self {
+        let mut tracker = Self::new();
+        tracker.restore_state(state);
+        tracker
+    }
+
     fn is_in_unsafe_block(&self) -> bool {
         self.unsafe_depth > 0
     }
@@ -541,59 +1179,268 @@ impl OwnershipTracker {
         self.ownership.insert(var, state);
     }
     
+    /// Exact-match ownership lookup, kept for tests that exercise the
+    /// tracker directly -- production checks go through [`Self::check_place`]
+    /// instead, since it also accounts for moved ancestors/descendants.
+    #[allow(dead_code)]
     fn get_ownership(&self, var: &str) -> Option<&OwnershipState> {
         self.ownership.get(var)
     }
     
+    /// Exact-match borrow lookup, kept for tests -- production conflict
+    /// checks go through [`Self::get_overlapping_borrows`] instead.
+    #[allow(dead_code)]
     fn get_borrows(&self, var: &str) -> BorrowInfo {
         self.borrows.get(var).cloned().unwrap_or_default()
     }
-    
-    fn add_borrow(&mut self, from: String, to: String, kind: BorrowKind) {
+
+    /// Whether two places alias the same storage: either the usual
+    /// ancestor/descendant prefix relationship (borrowing `s` reaches
+    /// `s.a`; borrowing `s.a` reaches `s`), or -- when both project off a
+    /// base declared as a C++ `union` -- any two fields at all, since a
+    /// union's fields share one location no matter how disjoint their
+    /// names look. Plain structs keep the ordinary sibling-fields-don't-
+    /// overlap behavior; only a shared union base widens it.
+    fn places_overlap(&self, a: &Place, b: &Place) -> bool {
+        if a.base == b.base && self.union_bases.contains(&a.base) {
+            return true;
+        }
+        a.is_prefix_of(b) || b.is_prefix_of(a)
+    }
+
+    /// Every active borrow that would conflict with creating a new one on
+    /// `place`: borrows of the exact place, of an ancestor (borrowing `s`
+    /// blocks touching `s.a` through another reference), of a descendant
+    /// (borrowing `s.a` blocks a wholesale borrow of `s`), and -- for a
+    /// union -- of any other field of the same base. Borrows of disjoint
+    /// sibling paths like `s.a` and `s.b` on an ordinary struct don't
+    /// overlap and so don't appear here.
+    fn get_overlapping_borrows(&self, place: &str) -> BorrowInfo {
+        let place = Place::parse(place);
+        let mut combined = BorrowInfo::default();
+        for (key, info) in &self.borrows {
+            let other = Place::parse(key);
+            if self.places_overlap(&place, &other) {
+                for (borrower, detail) in &info.borrowers {
+                    combined.borrowers.insert(borrower.clone(), detail.clone());
+                }
+            }
+        }
+        combined.recompute_counts();
+        combined
+    }
+
+    /// Path-aware ownership check for `place`: beyond the exact-match
+    /// cases [`OwnershipState::Moved`]/[`OwnershipState::MaybeMoved`]
+    /// already cover, this also catches reading through a moved ancestor
+    /// (`s` moved wholesale poisons `s.a`) and reading a place some
+    /// descendant of which was moved (`s.a` moved poisons reading `s` as a
+    /// whole, while leaving a disjoint sibling like `s.b` untouched).
+    fn check_place(&self, place: &str) -> MoveCheckResult {
+        let parsed = Place::parse(place);
+
+        match self.ownership.get(place) {
+            Some(OwnershipState::Moved) => return MoveCheckResult::Moved,
+            Some(OwnershipState::MaybeMoved) => return MoveCheckResult::MaybeMoved,
+            _ => {}
+        }
+
+        for ancestor in parsed.ancestors() {
+            if matches!(self.ownership.get(&ancestor.render()), Some(OwnershipState::Moved)) {
+                return MoveCheckResult::MovedViaParent { parent: ancestor.render() };
+            }
+        }
+
+        let child = self.ownership.iter().find(|(key, state)| {
+            **state == OwnershipState::Moved
+                && key.as_str() != place
+                && parsed.is_prefix_of(&Place::parse(key))
+        });
+        if let Some((child, _)) = child {
+            return MoveCheckResult::PartiallyMoved { child: child.clone() };
+        }
+
+        MoveCheckResult::Ok
+    }
+
+    /// Record `place` as moved, clearing any more specific sub-paths that
+    /// are now subsumed by the wholesale move -- e.g. moving `s` after
+    /// `s.a` alone had been moved makes the old `s.a` entry redundant.
+    fn mark_place_moved(&mut self, place: &str) {
+        let parsed = Place::parse(place);
+        self.ownership
+            .retain(|key, _| !(key.as_str() != place && parsed.is_prefix_of(&Place::parse(key))));
+        self.ownership.insert(place.to_string(), OwnershipState::Moved);
+    }
+
+    fn add_borrow(&mut self, from: String, to: String, kind: BorrowKind, created_at: usize, last_use: usize) {
         let borrow_info = self.borrows.entry(from).or_default();
-        borrow_info.borrowers.insert(to.clone());
-        
+        borrow_info.borrowers.insert(to.clone(), Loan { kind: kind.clone(), created_at, last_use });
+
         // Track this borrow in the current scope
         if let Some(current_scope) = self.scope_stack.last_mut() {
             current_scope.local_borrows.insert(to);
         }
-        
+
         match kind {
-            BorrowKind::Immutable => borrow_info.immutable_count += 1,
+            BorrowKind::Immutable | BorrowKind::TwoPhaseMutable => borrow_info.immutable_count += 1,
             BorrowKind::Mutable => borrow_info.has_mutable = true,
         }
     }
-    
+
+    /// Non-lexical liveness: drop every borrower (across all targets)
+    /// whose recorded last use already fell behind `current_index`, then
+    /// recompute each target's aggregate counts from what's left. Called
+    /// before checking a new borrow for conflicts, so a reference that's
+    /// never touched again doesn't keep constraining later borrows just
+    /// because its enclosing scope hasn't closed yet.
+    fn expire_borrows(&mut self, current_index: usize) {
+        for borrow_info in self.borrows.values_mut() {
+            borrow_info.borrowers.retain(|_, loan| loan.last_use >= current_index);
+            borrow_info.recompute_counts();
+        }
+        self.borrows.retain(|_, info| !info.borrowers.is_empty());
+    }
+
+    /// The place a still-active borrower was created from -- the reverse
+    /// of `add_borrow`'s `from -> to` recording. `Activate { reference }`
+    /// only has the borrower's own name to go on, so it needs this to find
+    /// the loan whose reservation it's turning exclusive.
+    fn borrow_source_of(&self, reference: &str) -> Option<String> {
+        self.borrows
+            .iter()
+            .find(|(_, info)| info.borrowers.contains_key(reference))
+            .map(|(place, _)| place.clone())
+    }
+
+    /// Turn a `BorrowKind::TwoPhaseMutable` reservation on `reference` into
+    /// its exclusive phase. This re-runs the same exclusivity check a
+    /// plain `Mutable` borrow runs at creation -- already immutably
+    /// borrowed is an error, already mutably borrowed (by some other loan)
+    /// is an error -- except against whatever is live *now*, which may
+    /// have changed since the reservation itself went unchallenged past
+    /// any shared borrows active at the time.
+    fn activate_two_phase_borrow(
+        &mut self,
+        reference: &str,
+        index: usize,
+        statements: &[crate::ir::IrStatement],
+        errors: &mut Vec<BorrowCheckError>,
+    ) {
+        self.expire_borrows(index);
+
+        let Some(place) = self.borrow_source_of(reference) else {
+            return;
+        };
+
+        let mut other_borrows = self.get_overlapping_borrows(&place);
+        other_borrows.borrowers.remove(reference);
+        other_borrows.recompute_counts();
+
+        if other_borrows.immutable_count > 0 {
+            let secondary = explain_borrow_conflict(statements, &other_borrows, BorrowKind::Immutable);
+            errors.push(BorrowCheckError::new(
+                ErrorKind::MutableBorrowWhileImmutable,
+                index,
+                format!("Cannot activate mutable reference to '{}': already immutably borrowed", place),
+            ).with_secondary(secondary));
+        } else if other_borrows.has_mutable {
+            let secondary = explain_borrow_conflict(statements, &other_borrows, BorrowKind::Mutable);
+            errors.push(BorrowCheckError::new(
+                ErrorKind::DoubleBorrow,
+                index,
+                format!("Cannot activate mutable reference to '{}': already mutably borrowed", place),
+            ).with_secondary(secondary));
+        }
+
+        if let Some(info) = self.borrows.get_mut(&place) {
+            if let Some(loan) = info.borrowers.get_mut(reference) {
+                loan.kind = BorrowKind::Mutable;
+            }
+            info.recompute_counts();
+        }
+        if let Some(info) = self.reference_info.get_mut(reference) {
+            info.is_mutable = true;
+        }
+        self.mark_used_mut(reference.to_string());
+    }
+
     fn enter_scope(&mut self) {
         self.scope_stack.push(ScopeInfo::default());
     }
     
-    fn exit_scope(&mut self) {
+    /// Pop the current scope, reporting a dangling-reference error for any
+    /// reference bound in it whose referent doesn't survive the pop: its
+    /// region (the referent's own scope depth) is at least as deep as the
+    /// scope closing, while the reference itself was declared further out
+    /// and so is about to keep being used past the value it points to.
+    /// A reference whose referent lives shallower than this scope (e.g. a
+    /// function parameter rebound three blocks deep) is untouched by that
+    /// check, and a reference that dies in this same scope alongside its
+    /// referent isn't dangling at all -- both are filtered out below
+    /// before any error is raised.
+    fn exit_scope(&mut self, index: usize, errors: &mut Vec<BorrowCheckError>) {
+        let exiting_depth = self.scope_stack.len();
         if let Some(scope) = self.scope_stack.pop() {
             // Clean up all borrows created in this scope
             for borrow_name in scope.local_borrows {
+                let referent_dies_here = self
+                    .reference_info
+                    .get(&borrow_name)
+                    .map(|info| info.region >= exiting_depth)
+                    .unwrap_or(false);
+                let reference_escapes = self.declared_scope.get(&borrow_name).copied().unwrap_or(exiting_depth) < exiting_depth;
+
+                if referent_dies_here && reference_escapes {
+                    errors.push(BorrowCheckError::new(
+                        ErrorKind::DanglingReference,
+                        index,
+                        format!(
+                            "'{}' would outlive the value it borrows: that value's scope just ended",
+                            borrow_name
+                        ),
+                    ));
+                }
+
                 // Remove from reference info
                 self.reference_info.remove(&borrow_name);
-                
+
                 // Remove from all borrow tracking
                 for borrow_info in self.borrows.values_mut() {
                     borrow_info.borrowers.remove(&borrow_name);
-                    // Note: In a more complete implementation, we'd also
-                    // decrement counts based on the borrow kind
                 }
             }
-            
+
+            for borrow_info in self.borrows.values_mut() {
+                borrow_info.recompute_counts();
+            }
+
             // Clean up empty borrow entries
             self.borrows.retain(|_, info| !info.borrowers.is_empty());
         }
     }
     
-    fn mark_as_reference(&mut self, var: String, is_mutable: bool) {
+    fn mark_as_reference(&mut self, var: String, is_mutable: bool, region: usize) {
         self.reference_info.insert(var, ReferenceInfo {
             is_reference: true,
             is_mutable,
+            region,
         });
     }
+
+    /// Record the scope depth `place` was first observed at, if it hasn't
+    /// been recorded already -- a place's declared scope never changes
+    /// once set.
+    fn record_declaration(&mut self, place: &str) {
+        let depth = self.scope_stack.len();
+        self.declared_scope.entry(place.to_string()).or_insert(depth);
+    }
+
+    /// The scope depth `place` lives in, i.e. its loan's region -- the
+    /// function's own root scope (depth 1) if never otherwise recorded.
+    fn region_of(&self, place: &str) -> usize {
+        self.declared_scope.get(place).copied().unwrap_or(1)
+    }
     
     fn is_reference(&self, var: &str) -> bool {
         self.reference_info
@@ -608,80 +1455,79 @@ impl OwnershipTracker {
             .map(|info| info.is_reference && info.is_mutable)
             .unwrap_or(false)
     }
-    
-    fn enter_loop(&mut self) {
-        // Save current state when entering a loop
-        // This state represents the state at the END of the first iteration
-        // which is what we'll use to check the BEGINNING of the second iteration
-        self.loop_entry_states.push(LoopEntryState {
-            ownership: self.ownership.clone(),
-            borrows: self.borrows.clone(),
-        });
-        self.loop_depth += 1;
+
+    /// `var`'s region -- the scope depth of whatever it currently points
+    /// to -- if it's a reference at all.
+    fn reference_region(&self, var: &str) -> Option<usize> {
+        self.reference_info.get(var).filter(|info| info.is_reference).map(|info| info.region)
     }
-    
-    fn exit_loop(&mut self) {
-        if self.loop_depth > 0 {
-            self.loop_depth -= 1;
-            
-            // When exiting a loop, we simulate having run it twice
-            // The current state is after one iteration
-            // We saved the state at loop entry, now apply the second iteration effects
-            if let Some(entry_state) = self.loop_entry_states.pop() {
-                // The key insight: variables that were moved in the loop body
-                // will be moved at the START of the second iteration
-                // So check if any variables that are currently Moved
-                // were NOT moved at loop entry
-                for (var, current_state) in &self.ownership {
-                    if *current_state == OwnershipState::Moved {
-                        // If this variable was Owned at loop entry,
-                        // it means it was moved during the loop body
-                        // On second iteration, it would already be Moved
-                        if let Some(entry_ownership) = entry_state.ownership.get(var) {
-                            if *entry_ownership == OwnershipState::Owned {
-                                // Keep it as Moved - this correctly represents
-                                // the state after 2 iterations
-                                // The error will be caught if the variable is used
-                                // in the loop body (which we already processed)
-                            }
-                        }
-                    }
-                }
-            }
-        }
+
+    fn mark_used_mut(&mut self, var: String) {
+        self.used_mut.insert(var);
     }
-    
+
+    fn record_reservation_conflict(&mut self, message: String) {
+        self.reservation_warnings.insert(message);
+    }
+
+    /// Note that `to`'s loan on `from` was accepted without an aliasing
+    /// check because it was created inside an unsafe region -- the
+    /// checker trusts the author's own reasoning there instead of proving
+    /// exclusivity itself, so it's worth a "you relied on manual
+    /// reasoning here" line in the summary rather than silence.
+    fn record_unchecked_loan(&mut self, from: &str, to: &str, index: usize) {
+        self.unchecked_loans.insert(format!(
+            "unchecked: borrow of '{}' via '{}' at statement {} was not alias-checked (inside an unsafe region)",
+            from, to, index
+        ));
+    }
+
     fn clone_state(&self) -> TrackerState {
         TrackerState {
             ownership: self.ownership.clone(),
             borrows: self.borrows.clone(),
             reference_info: self.reference_info.clone(),
+            used_mut: self.used_mut.clone(),
+            declared_scope: self.declared_scope.clone(),
+            reservation_warnings: self.reservation_warnings.clone(),
+            union_bases: self.union_bases.clone(),
+            unchecked_loans: self.unchecked_loans.clone(),
         }
     }
-    
+
     fn restore_state(&mut self, state: &TrackerState) {
         self.ownership = state.ownership.clone();
         self.borrows = state.borrows.clone();
         self.reference_info = state.reference_info.clone();
+        self.used_mut = state.used_mut.clone();
+        self.declared_scope = state.declared_scope.clone();
+        self.reservation_warnings = state.reservation_warnings.clone();
+        self.union_bases = state.union_bases.clone();
+        self.unchecked_loans = state.unchecked_loans.clone();
     }
     
     fn merge_states(&mut self, then_state: &TrackerState, else_state: &TrackerState) {
-        // Merge ownership states conservatively
-        // A variable is considered moved only if moved in BOTH branches
-        for (var, then_ownership) in &then_state.ownership {
-            if let Some(else_ownership) = else_state.ownership.get(var) {
-                if *then_ownership == OwnershipState::Moved && *else_ownership == OwnershipState::Moved {
-                    // Moved in both branches - stays moved
-                    self.ownership.insert(var.clone(), OwnershipState::Moved);
-                } else if *then_ownership == OwnershipState::Moved || *else_ownership == OwnershipState::Moved {
-                    // Moved in only one branch - mark as "maybe moved" (for now, treat as owned)
-                    // In a more sophisticated analysis, we'd track MaybeMoved state
-                    self.ownership.insert(var.clone(), OwnershipState::Owned);
-                } else {
-                    // Not moved in either branch - use the common state
-                    self.ownership.insert(var.clone(), then_ownership.clone());
-                }
-            }
+        // Merge ownership per move path (not just per whole variable), so
+        // e.g. `x.a` and `x.b` join independently of each other and of
+        // `x` itself -- each is just its own key in this map. A path
+        // present on only one arm (a field moved for the first time
+        // inside that branch, say, with no entry for it before the `if`)
+        // still has to come out `MaybeMoved` rather than being forgotten,
+        // since the other arm implicitly leaves it untouched and therefore
+        // live -- the same conservative three-valued join `join_ownership`
+        // already does for paths that do have an entry on both sides.
+        let mut paths: HashSet<&String> = then_state.ownership.keys().collect();
+        paths.extend(else_state.ownership.keys());
+        for var in paths {
+            let merged = match (then_state.ownership.get(var), else_state.ownership.get(var)) {
+                (Some(then_ownership), Some(else_ownership)) => join_ownership(then_ownership, else_ownership),
+                (Some(one), None) | (None, Some(one)) => match one {
+                    OwnershipState::Moved | OwnershipState::MaybeMoved => OwnershipState::MaybeMoved,
+                    other => other.clone(),
+                },
+                (None, None) => unreachable!("var came from one of the two maps we just collected keys from"),
+            };
+            self.ownership.insert(var.clone(), merged);
         }
         
         // Merge borrows - a borrow exists only if it exists in BOTH branches
@@ -692,11 +1538,9 @@ impl OwnershipTracker {
                 // Borrow exists in both branches - keep it
                 let mut merged_borrow = then_borrow.clone();
                 // Keep only common borrowers
-                merged_borrow.borrowers.retain(|b| else_borrow.borrowers.contains(b));
-                // Use minimum counts (conservative)
-                merged_borrow.immutable_count = merged_borrow.immutable_count.min(else_borrow.immutable_count);
-                merged_borrow.has_mutable = merged_borrow.has_mutable && else_borrow.has_mutable;
-                
+                merged_borrow.borrowers.retain(|b, _| else_borrow.borrowers.contains_key(b));
+                merged_borrow.recompute_counts();
+
                 if !merged_borrow.borrowers.is_empty() {
                     self.borrows.insert(var.clone(), merged_borrow);
                 }
@@ -712,39 +1556,40 @@ impl OwnershipTracker {
             }
         }
         self.reference_info.retain(|var, _| refs_to_keep.contains(var));
-    }
-    
-    fn clear_loop_locals(&mut self, loop_locals: &HashSet<String>) {
-        // Clear borrows for loop-local variables
-        for local_var in loop_locals {
-            // Remove from reference info
-            self.reference_info.remove(local_var);
-            
-            // Remove from all borrow tracking
-            for borrow_info in self.borrows.values_mut() {
-                borrow_info.borrowers.remove(local_var);
-                // We should also decrement counts, but need to track the kind
-                // For simplicity, we'll rebuild the counts
-            }
-            
-            // Remove the ownership entry for loop-local variables
-            self.ownership.remove(local_var);
-        }
-        
-        // Clean up empty borrow entries and recalculate counts
-        for (_, borrow_info) in self.borrows.iter_mut() {
-            // Reset counts based on remaining borrowers
-            // This is a simplification - in a real implementation we'd track
-            // the kind of each borrow
-            if borrow_info.borrowers.is_empty() {
-                borrow_info.immutable_count = 0;
-                borrow_info.has_mutable = false;
-            }
+
+        // A mutable reference used on either arm was used on some path,
+        // so union rather than intersect -- unlike borrows/references,
+        // "used mutably" only ever needs one path to be true.
+        self.used_mut.extend(then_state.used_mut.iter().cloned());
+        self.used_mut.extend(else_state.used_mut.iter().cloned());
+
+        // A place's declared scope is fixed once observed, so union
+        // rather than intersect -- unlike borrows, a place declared on
+        // only one arm is still declared after the `if`.
+        for (var, &depth) in then_state.declared_scope.iter().chain(else_state.declared_scope.iter()) {
+            self.declared_scope
+                .entry(var.clone())
+                .and_modify(|existing| *existing = (*existing).min(depth))
+                .or_insert(depth);
         }
-        
-        // Remove empty entries
-        self.borrows.retain(|_, info| !info.borrowers.is_empty());
+
+        // Same reasoning as `used_mut`: a reservation conflict flagged on
+        // either arm happened on some real path, so union rather than
+        // intersect.
+        self.reservation_warnings.extend(then_state.reservation_warnings.iter().cloned());
+        self.reservation_warnings.extend(else_state.reservation_warnings.iter().cloned());
+
+        // Same reasoning again: a loan accepted unchecked inside an
+        // unsafe region on either arm is still a loan the checker never
+        // proved exclusive on some real path.
+        self.unchecked_loans.extend(then_state.unchecked_loans.iter().cloned());
+        self.unchecked_loans.extend(else_state.unchecked_loans.iter().cloned());
+
+        // Also fixed once observed, like `declared_scope`.
+        self.union_bases.extend(then_state.union_bases.iter().cloned());
+        self.union_bases.extend(else_state.union_bases.iter().cloned());
     }
+
 }
 
 #[cfg(test)]
@@ -816,14 +1661,15 @@ mod tests {
         let mut tracker = OwnershipTracker::new();
         tracker.set_ownership("x".to_string(), OwnershipState::Owned);
         
-        // Add immutable borrow
-        tracker.add_borrow("x".to_string(), "ref1".to_string(), BorrowKind::Immutable);
+        // Add immutable borrow. `usize::MAX` as the last-use means "lives
+        // until its scope exits", i.e. never auto-expired by `expire_borrows`.
+        tracker.add_borrow("x".to_string(), "ref1".to_string(), BorrowKind::Immutable, 0, usize::MAX);
         let borrows = tracker.get_borrows("x");
         assert_eq!(borrows.immutable_count, 1);
         assert!(!borrows.has_mutable);
-        
+
         // Add another immutable borrow
-        tracker.add_borrow("x".to_string(), "ref2".to_string(), BorrowKind::Immutable);
+        tracker.add_borrow("x".to_string(), "ref2".to_string(), BorrowKind::Immutable, 1, usize::MAX);
         let borrows = tracker.get_borrows("x");
         assert_eq!(borrows.immutable_count, 2);
         assert!(!borrows.has_mutable);
@@ -833,14 +1679,29 @@ mod tests {
     fn test_mutable_borrow_tracking() {
         let mut tracker = OwnershipTracker::new();
         tracker.set_ownership("x".to_string(), OwnershipState::Owned);
-        
+
         // Add mutable borrow
-        tracker.add_borrow("x".to_string(), "mut_ref".to_string(), BorrowKind::Mutable);
+        tracker.add_borrow("x".to_string(), "mut_ref".to_string(), BorrowKind::Mutable, 0, usize::MAX);
         let borrows = tracker.get_borrows("x");
         assert_eq!(borrows.immutable_count, 0);
         assert!(borrows.has_mutable);
     }
 
+    #[test]
+    fn test_expire_borrows_drops_reference_past_its_last_use() {
+        let mut tracker = OwnershipTracker::new();
+        tracker.set_ownership("x".to_string(), OwnershipState::Owned);
+
+        // `ref_a` is only used up through statement 0; by statement 2 it's
+        // no longer live, so expiring at that index should drop it even
+        // though its enclosing scope is still open.
+        tracker.add_borrow("x".to_string(), "ref_a".to_string(), BorrowKind::Mutable, 0, 0);
+        tracker.expire_borrows(2);
+
+        let borrows = tracker.get_borrows("x");
+        assert!(!borrows.has_mutable, "expired borrow should no longer count");
+    }
+
     #[test]
     fn test_use_after_move_detection() {
         let mut program = create_test_program();
@@ -967,6 +1828,175 @@ mod tests {
         assert_eq!(errors.len(), 1);
         assert!(errors[0].contains("Cannot"));
         assert!(errors[0].contains("mutable"));
+        assert!(
+            errors[0].contains("'ref1' was borrowed at statement"),
+            "conflict should name the originating borrow: {}",
+            errors[0]
+        );
+    }
+
+    #[test]
+    fn test_unsafe_region_suppresses_aliasing_conflicts_but_reports_unchecked() {
+        let mut program = create_test_program();
+        let mut func = create_test_function("test");
+
+        func.variables.insert(
+            "x".to_string(),
+            crate::ir::VariableInfo {
+                name: "x".to_string(),
+                ty: crate::ir::VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let block = &mut func.cfg[petgraph::graph::NodeIndex::new(0)];
+        block.statements.push(IrStatement::EnterUnsafe);
+        block.statements.push(IrStatement::Borrow {
+            from: "x".to_string(),
+            to: "ref1".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+        // Would conflict with `ref1` outside an unsafe region.
+        block.statements.push(IrStatement::Borrow {
+            from: "x".to_string(),
+            to: "ref2".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+        // Keep `ref1` alive past `ref2`'s creation point -- otherwise
+        // non-lexical lifetimes would expire the never-read `ref1` first
+        // and the conflict this test cares about would never be attempted.
+        block.statements.push(IrStatement::Read(vec!["ref1".to_string()]));
+        block.statements.push(IrStatement::ExitUnsafe);
+
+        program.functions.push(func);
+
+        let (errors, warnings) = check_function(&program.functions[0]).unwrap();
+        assert!(errors.is_empty(), "aliasing conflicts inside an unsafe region should not be reported: {:?}", errors);
+        assert!(
+            warnings.iter().any(|w| w.starts_with("unchecked:") && w.contains("ref2")),
+            "an unsafe-accepted loan should show up in the summary: {:?}",
+            warnings
+        );
+    }
+
+    #[test]
+    fn test_use_after_move_still_errors_inside_unsafe_region() {
+        let mut program = create_test_program();
+        let mut func = create_test_function("test");
+
+        func.variables.insert(
+            "x".to_string(),
+            crate::ir::VariableInfo {
+                name: "x".to_string(),
+                ty: crate::ir::VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let block = &mut func.cfg[petgraph::graph::NodeIndex::new(0)];
+        block.statements.push(IrStatement::EnterUnsafe);
+        block.statements.push(IrStatement::Move {
+            from: "x".to_string(),
+            to: "y".to_string(),
+        });
+        // Using `x` again after it moved is a soundness issue, not an
+        // aliasing one -- the unsafe region must not hide it.
+        block.statements.push(IrStatement::Move {
+            from: "x".to_string(),
+            to: "z".to_string(),
+        });
+        block.statements.push(IrStatement::ExitUnsafe);
+
+        program.functions.push(func);
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+
+        let errors = result.unwrap();
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].contains("Use after move"));
+    }
+
+    #[test]
+    fn test_disjoint_struct_fields_may_both_be_mutably_borrowed() {
+        let mut program = create_test_program();
+        let mut func = create_test_function("test");
+
+        func.variables.insert(
+            "s".to_string(),
+            crate::ir::VariableInfo {
+                name: "s".to_string(),
+                ty: crate::ir::VariableType::Owned("Pair".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let block = &mut func.cfg[petgraph::graph::NodeIndex::new(0)];
+
+        // `s.a` and `s.b` are disjoint sibling fields of an ordinary
+        // struct, so borrowing both mutably at once is sound.
+        block.statements.push(IrStatement::Borrow {
+            from: "s.a".to_string(),
+            to: "ref_a".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+        block.statements.push(IrStatement::Borrow {
+            from: "s.b".to_string(),
+            to: "ref_b".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+
+        program.functions.push(func);
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().len(), 0, "disjoint struct fields don't alias");
+    }
+
+    #[test]
+    fn test_union_fields_alias_and_conflict() {
+        let mut program = create_test_program();
+        let mut func = create_test_function("test");
+
+        func.variables.insert(
+            "u".to_string(),
+            crate::ir::VariableInfo {
+                name: "u".to_string(),
+                ty: crate::ir::VariableType::Union("Variant".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let block = &mut func.cfg[petgraph::graph::NodeIndex::new(0)];
+
+        // `u.a` and `u.b` share the same storage in a union, even though
+        // they look like disjoint sibling fields -- a mutable borrow of
+        // one must conflict with a mutable borrow of the other. The
+        // trailing `Read` keeps `ref_a` non-lexically alive past the
+        // second borrow instead of expiring right after its own creation.
+        block.statements.push(IrStatement::Borrow {
+            from: "u.a".to_string(),
+            to: "ref_a".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+        block.statements.push(IrStatement::Borrow {
+            from: "u.b".to_string(),
+            to: "ref_b".to_string(),
+            kind: BorrowKind::Mutable,
+        });
+        block.statements.push(IrStatement::Read(vec!["ref_a".to_string()]));
+
+        program.functions.push(func);
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        let errors = result.unwrap();
+        assert_eq!(errors.len(), 1, "union fields alias, so a second mutable borrow conflicts");
+        assert!(errors[0].contains("u.b"));
     }
 
     #[test]
@@ -1523,4 +2553,254 @@ mod scope_tests {
         assert!(errors.len() > 0, "Should still catch errors within the same scope");
         assert!(errors[0].contains("already mutably borrowed"));
     }
+
+    #[test]
+    fn test_inner_scope_borrow_kind_doesnt_leak_into_outer_scope() {
+        // An immutable borrow created (and retired) inside an inner scope
+        // shouldn't leave behind any trace -- `immutable_count`,
+        // `has_mutable` -- that would make a differently-kinded borrow in
+        // the outer scope afterwards look like a conflict.
+        let statements = vec![
+            IrStatement::EnterScope,
+            IrStatement::Borrow {
+                from: "value".to_string(),
+                to: "ref1".to_string(),
+                kind: BorrowKind::Immutable,
+            },
+            IrStatement::ExitScope,
+            // ref1 is gone; a mutable borrow of the same place afterwards
+            // should see a clean slate, not a leftover immutable count.
+            IrStatement::Borrow {
+                from: "value".to_string(),
+                to: "ref2".to_string(),
+                kind: BorrowKind::Mutable,
+            },
+        ];
+
+        let func = create_test_function_with_statements(statements);
+        let mut program = IrProgram {
+            functions: vec![func],
+            ownership_graph: petgraph::graph::DiGraph::new(),
+        };
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        let errors = result.unwrap();
+        assert_eq!(
+            errors.len(),
+            0,
+            "a borrow retired at scope exit must not block a differently-kinded borrow afterwards"
+        );
+    }
+}
+
+#[cfg(test)]
+mod dataflow_tests {
+    use super::*;
+    use crate::ir::{BasicBlock, IrExpression, IrFunction, IrProgram, IrStatement, VariableInfo, VariableType};
+    use petgraph::graph::DiGraph;
+    use std::collections::HashMap;
+
+    /// A loop CFG: `entry -> header -> body -> header` (the back edge)
+    /// and `header -> after_loop`. The body unconditionally moves `x`, so
+    /// a real fixpoint has to join the back edge's `Moved` exit into the
+    /// header's entry and re-run the body from that joined (`MaybeMoved`)
+    /// entry before it settles -- a single forward pass over the blocks
+    /// would miss this and report no error at all.
+    #[test]
+    fn test_loop_back_edge_is_joined_to_a_fixpoint() {
+        let mut cfg = DiGraph::new();
+
+        let entry = cfg.add_node(BasicBlock { id: 0, statements: Vec::new(), terminator: None });
+        let header = cfg.add_node(BasicBlock { id: 1, statements: Vec::new(), terminator: None });
+        let body = cfg.add_node(BasicBlock {
+            id: 2,
+            statements: vec![IrStatement::Move { from: "x".to_string(), to: "y".to_string() }],
+            terminator: None,
+        });
+        let after_loop = cfg.add_node(BasicBlock { id: 3, statements: Vec::new(), terminator: None });
+
+        cfg.add_edge(entry, header, ());
+        cfg.add_edge(header, body, ());
+        cfg.add_edge(body, header, ()); // back edge
+        cfg.add_edge(header, after_loop, ());
+
+        let mut variables = HashMap::new();
+        variables.insert(
+            "x".to_string(),
+            VariableInfo {
+                name: "x".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let func = IrFunction { name: "test".to_string(), cfg, variables };
+        let program = IrProgram {
+            functions: vec![func],
+            ownership_graph: petgraph::graph::DiGraph::new(),
+        };
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        let errors = result.unwrap();
+        assert_eq!(
+            errors.len(),
+            1,
+            "joining the back edge to a fixpoint should catch the repeated move on a later iteration"
+        );
+        assert!(errors[0].contains('x'));
+    }
+
+    /// Same loop shape as above, but the body mutably borrows `x` into
+    /// `r` on every pass instead of moving it. `r` is never used again
+    /// within the body, so nothing *inside* one iteration would ever
+    /// flag it -- only joining the back edge's exit state (still
+    /// carrying that live mutable borrow) into the header's entry, and
+    /// re-running the body from it, surfaces the second iteration's
+    /// borrow as conflicting with the first's.
+    #[test]
+    fn test_loop_back_edge_joins_borrows_across_iterations() {
+        let mut cfg = DiGraph::new();
+
+        let entry = cfg.add_node(BasicBlock { id: 0, statements: Vec::new(), terminator: None });
+        let header = cfg.add_node(BasicBlock { id: 1, statements: Vec::new(), terminator: None });
+        let body = cfg.add_node(BasicBlock {
+            id: 2,
+            statements: vec![IrStatement::Borrow { from: "x".to_string(), to: "r".to_string(), kind: BorrowKind::Mutable }],
+            terminator: None,
+        });
+        let after_loop = cfg.add_node(BasicBlock { id: 3, statements: Vec::new(), terminator: None });
+
+        cfg.add_edge(entry, header, ());
+        cfg.add_edge(header, body, ());
+        cfg.add_edge(body, header, ()); // back edge
+        cfg.add_edge(header, after_loop, ());
+
+        let mut variables = HashMap::new();
+        variables.insert(
+            "x".to_string(),
+            VariableInfo {
+                name: "x".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let func = IrFunction { name: "test".to_string(), cfg, variables };
+        let program = IrProgram {
+            functions: vec![func],
+            ownership_graph: petgraph::graph::DiGraph::new(),
+        };
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        let errors = result.unwrap();
+        assert_eq!(
+            errors.len(),
+            1,
+            "joining the back edge to a fixpoint should catch the repeated mutable borrow on a later iteration"
+        );
+        assert!(errors[0].contains('x'));
+    }
+
+    /// A diamond CFG: `entry -> (then | else) -> after`. Only the `then`
+    /// branch moves `x`; a straight-line check of either block alone would
+    /// see nothing wrong, and a join that only reported `Moved` when
+    /// *every* predecessor agreed would also miss it. Only the conservative
+    /// "moved on any incoming path" join produces `MaybeMoved` at `after`,
+    /// which the trailing use must then flag as use of a possibly-moved
+    /// value -- exercising the same `MaybeMoved` branch-merge diagnostic
+    /// `process_statement`'s `Assign` arm already carries, but at a real
+    /// two-predecessor CFG join instead of a single `If` statement.
+    #[test]
+    fn test_if_else_join_reports_maybe_moved_on_divergent_branch() {
+        let mut cfg = DiGraph::new();
+
+        let entry = cfg.add_node(BasicBlock { id: 0, statements: Vec::new(), terminator: None });
+        let then_branch = cfg.add_node(BasicBlock {
+            id: 1,
+            statements: vec![IrStatement::Move { from: "x".to_string(), to: "y".to_string() }],
+            terminator: None,
+        });
+        let else_branch = cfg.add_node(BasicBlock { id: 2, statements: Vec::new(), terminator: None });
+        let after = cfg.add_node(BasicBlock {
+            id: 3,
+            statements: vec![IrStatement::Assign { lhs: "z".to_string(), rhs: IrExpression::Variable("x".to_string()) }],
+            terminator: None,
+        });
+
+        cfg.add_edge(entry, then_branch, ());
+        cfg.add_edge(entry, else_branch, ());
+        cfg.add_edge(then_branch, after, ());
+        cfg.add_edge(else_branch, after, ());
+
+        let mut variables = HashMap::new();
+        variables.insert(
+            "x".to_string(),
+            VariableInfo {
+                name: "x".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let func = IrFunction { name: "test".to_string(), cfg, variables };
+        let program = IrProgram {
+            functions: vec![func],
+            ownership_graph: petgraph::graph::DiGraph::new(),
+        };
+
+        let result = check_borrows(program);
+        assert!(result.is_ok());
+        let errors = result.unwrap();
+        assert_eq!(
+            errors.len(),
+            1,
+            "a variable moved on only one incoming branch must join to maybe-moved and be flagged at the later read"
+        );
+        assert!(errors[0].contains('x'));
+    }
+
+    /// `RegionAnalysis::compute` should surface the same loan the pass/fail
+    /// checker tracks internally: a borrow of `x` into `r`, live from its
+    /// creation at statement 0 through its last use (the `Read`) at
+    /// statement 1.
+    #[test]
+    fn test_region_analysis_reports_borrow_live_range() {
+        let mut cfg = DiGraph::new();
+        cfg.add_node(BasicBlock {
+            id: 0,
+            statements: vec![
+                IrStatement::Borrow { from: "x".to_string(), to: "r".to_string(), kind: BorrowKind::Immutable },
+                IrStatement::Read(vec!["r".to_string()]),
+            ],
+            terminator: None,
+        });
+
+        let mut variables = HashMap::new();
+        variables.insert(
+            "x".to_string(),
+            VariableInfo {
+                name: "x".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Owned,
+                lifetime: None,
+            },
+        );
+
+        let func = IrFunction { name: "test".to_string(), cfg, variables };
+        let analysis = RegionAnalysis::compute(&func);
+
+        assert_eq!(analysis.regions.len(), 1, "got: {:?}", analysis.regions);
+        let region = &analysis.regions[0];
+        assert_eq!(region.target, "x");
+        assert_eq!(region.borrower, "r");
+        assert_eq!(region.kind, BorrowKind::Immutable);
+        assert_eq!(region.created_at, 0);
+        assert_eq!(region.last_use, 1);
+    }
 }
@@ -0,0 +1,194 @@
+//! Field-sensitive move paths -- a miniature of rustc's `MoveData`.
+//!
+//! Ownership is otherwise tracked as a flat map keyed on whatever string
+//! identifies a place, so moving `s.a` and moving `s` look identical to a
+//! plain string comparison. A [`Place`] decomposes that string into a base
+//! variable plus the chain of field/index projections needed to reach a
+//! specific sub-object, so the checker can tell "the whole of `s`" apart
+//! from "just `s.a`" and let disjoint fields move and borrow independently.
+
+/// One step of a place's projection chain off its base variable.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Projection {
+    Field(String),
+    Index,
+}
+
+/// A base variable plus the projections needed to reach a specific
+/// sub-object of it, e.g. `s.a` is `{ base: "s", projections: [Field("a")] }`.
+/// A plain variable name parses to an empty projection chain, so every
+/// existing whole-variable place behaves exactly as it did before this
+/// module existed.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Place {
+    pub base: String,
+    pub projections: Vec<Projection>,
+}
+
+impl Place {
+    /// Parse a dotted/bracketed place string -- the only representation
+    /// the IR has for a place today -- into its base and projections.
+    pub fn parse(raw: &str) -> Self {
+        let mut base = None;
+        let mut projections = Vec::new();
+        let mut current = String::new();
+        let mut chars = raw.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '.' => {
+                    if base.is_none() {
+                        base = Some(std::mem::take(&mut current));
+                    } else if !current.is_empty() {
+                        projections.push(Projection::Field(std::mem::take(&mut current)));
+                    }
+                }
+                '[' => {
+                    if base.is_none() {
+                        base = Some(std::mem::take(&mut current));
+                    } else if !current.is_empty() {
+                        projections.push(Projection::Field(std::mem::take(&mut current)));
+                    }
+                    // The index expression itself doesn't distinguish
+                    // places for our purposes -- skip to the matching ']'.
+                    for c2 in chars.by_ref() {
+                        if c2 == ']' {
+                            break;
+                        }
+                    }
+                    projections.push(Projection::Index);
+                }
+                _ => current.push(c),
+            }
+        }
+
+        match base {
+            Some(base) => {
+                if !current.is_empty() {
+                    projections.push(Projection::Field(current));
+                }
+                Place { base, projections }
+            }
+            None => Place { base: current, projections },
+        }
+    }
+
+    /// Re-render this place back to the canonical string used as a map
+    /// key everywhere else in the checker.
+    pub fn render(&self) -> String {
+        let mut out = self.base.clone();
+        for projection in &self.projections {
+            match projection {
+                Projection::Field(name) => {
+                    out.push('.');
+                    out.push_str(name);
+                }
+                Projection::Index => out.push_str("[]"),
+            }
+        }
+        out
+    }
+
+    /// True if `self` is `other`, or an ancestor of it -- i.e. an
+    /// operation on `self` as a whole also reaches everything under
+    /// `other` (moving `s` moves `s.a`; reading `s.a` is reading part of `s`).
+    pub fn is_prefix_of(&self, other: &Place) -> bool {
+        self.base == other.base
+            && self.projections.len() <= other.projections.len()
+            && self.projections[..] == other.projections[..self.projections.len()]
+    }
+
+    /// Every proper ancestor of this place, closest first: `s.a.b` yields
+    /// `s.a` then `s`.
+    pub fn ancestors(&self) -> impl Iterator<Item = Place> + '_ {
+        (0..self.projections.len()).rev().map(move |len| Place {
+            base: self.base.clone(),
+            projections: self.projections[..len].to_vec(),
+        })
+    }
+}
+
+/// The outcome of checking whether a place can be read from or moved out
+/// of, given every place that has already been recorded as moved.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MoveCheckResult {
+    /// Not moved, not shadowed by a moved ancestor or descendant.
+    Ok,
+    /// This exact place was moved on every path reaching here.
+    Moved,
+    /// This exact place was moved on some, but not all, paths reaching here.
+    MaybeMoved,
+    /// An ancestor place was moved wholesale, e.g. reading `s.a` after `s`
+    /// itself was moved.
+    MovedViaParent { parent: String },
+    /// A descendant place was moved, e.g. reading `s` after `s.a` was
+    /// moved out of it -- `s.b` would still be fine, but `s` as a whole
+    /// can't be read or moved until `s.a` is reinitialized.
+    PartiallyMoved { child: String },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_plain_variable_with_no_projections() {
+        let place = Place::parse("s");
+        assert_eq!(place.base, "s");
+        assert!(place.projections.is_empty());
+    }
+
+    #[test]
+    fn parses_field_projection() {
+        let place = Place::parse("s.a");
+        assert_eq!(place.base, "s");
+        assert_eq!(place.projections, vec![Projection::Field("a".to_string())]);
+        assert_eq!(place.render(), "s.a");
+    }
+
+    #[test]
+    fn parses_nested_field_projection() {
+        let place = Place::parse("s.a.b");
+        assert_eq!(
+            place.projections,
+            vec![Projection::Field("a".to_string()), Projection::Field("b".to_string())]
+        );
+    }
+
+    #[test]
+    fn parses_index_projection() {
+        let place = Place::parse("arr[0]");
+        assert_eq!(place.base, "arr");
+        assert_eq!(place.projections, vec![Projection::Index]);
+    }
+
+    #[test]
+    fn whole_variable_is_prefix_of_its_fields() {
+        let s = Place::parse("s");
+        let s_a = Place::parse("s.a");
+        assert!(s.is_prefix_of(&s_a));
+        assert!(!s_a.is_prefix_of(&s));
+    }
+
+    #[test]
+    fn sibling_fields_are_not_prefixes_of_each_other() {
+        let s_a = Place::parse("s.a");
+        let s_b = Place::parse("s.b");
+        assert!(!s_a.is_prefix_of(&s_b));
+        assert!(!s_b.is_prefix_of(&s_a));
+    }
+
+    #[test]
+    fn different_base_variables_never_overlap() {
+        let s_a = Place::parse("s.a");
+        let t_a = Place::parse("t.a");
+        assert!(!s_a.is_prefix_of(&t_a));
+    }
+
+    #[test]
+    fn ancestors_walk_up_from_closest_to_farthest() {
+        let place = Place::parse("s.a.b");
+        let ancestors: Vec<Place> = place.ancestors().collect();
+        assert_eq!(ancestors, vec![Place::parse("s.a"), Place::parse("s")]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::diagnostics::{Applicability, BorrowCheckDiagnostic, Location, Severity, Suggestion};
 use crate::parser::{Statement, Expression, Function};
 
 /// Check for unsafe pointer operations in a function's AST
@@ -8,77 +9,165 @@ pub fn check_function_for_pointers(function: &crate::ir::IrFunction) -> Result<V
 }
 
 /// Check for unsafe pointer operations in a parsed function
-pub fn check_parsed_function_for_pointers(function: &Function) -> Vec<String> {
-    let mut errors = Vec::new();
-    
-    for stmt in &function.body {
-        if let Some(error) = check_parsed_statement_for_pointers(stmt) {
-            errors.push(format!("In function '{}': {}", function.name, error));
+pub fn check_parsed_function_for_pointers(function: &Function) -> Vec<BorrowCheckDiagnostic> {
+    let mut diagnostics = Vec::new();
+    check_statements_for_pointers(&function.body, &function.name, &function.location, 0, &mut diagnostics);
+    diagnostics
+}
+
+/// Recursively walk `statements`, reporting pointer operations except
+/// where `in_unsafe` is nonzero. `in_unsafe` is a depth rather than a
+/// flag because an `unsafe` block is never un-entered by the blocks
+/// nested inside it -- mirroring Rust, where an `unsafe {}` inside
+/// another `unsafe {}` is still unsafe -- so every recursive call just
+/// carries the count forward (incrementing it only for `UnsafeBlock`
+/// itself) rather than resetting it.
+fn check_statements_for_pointers(
+    statements: &[Statement],
+    function_name: &str,
+    fallback_location: &crate::parser::SourceLocation,
+    in_unsafe: usize,
+    diagnostics: &mut Vec<BorrowCheckDiagnostic>,
+) {
+    for stmt in statements {
+        if in_unsafe == 0 {
+            if let Some(diagnostic) = check_parsed_statement_for_pointers(stmt, function_name, fallback_location) {
+                diagnostics.push(diagnostic);
+            }
+        }
+
+        match stmt {
+            Statement::UnsafeBlock { statements: inner, .. } => {
+                check_statements_for_pointers(inner, function_name, fallback_location, in_unsafe + 1, diagnostics);
+            }
+            Statement::Block(inner) => {
+                check_statements_for_pointers(inner, function_name, fallback_location, in_unsafe, diagnostics);
+            }
+            Statement::If { then_branch, else_branch, .. } => {
+                check_statements_for_pointers(then_branch, function_name, fallback_location, in_unsafe, diagnostics);
+                if let Some(else_branch) = else_branch {
+                    check_statements_for_pointers(else_branch, function_name, fallback_location, in_unsafe, diagnostics);
+                }
+            }
+            _ => {}
         }
     }
-    
-    errors
 }
 
-/// Check if a parsed statement contains pointer operations
-pub fn check_parsed_statement_for_pointers(stmt: &Statement) -> Option<String> {
+/// Check if a parsed statement contains pointer operations.
+///
+/// `fallback_location` is used for statement kinds (like bare `return`)
+/// that don't carry their own `SourceLocation` in the AST; it is the
+/// enclosing function's location so the diagnostic still points somewhere
+/// useful in the source.
+pub fn check_parsed_statement_for_pointers(
+    stmt: &Statement,
+    function_name: &str,
+    fallback_location: &crate::parser::SourceLocation,
+) -> Option<BorrowCheckDiagnostic> {
     use crate::parser::Statement;
-    
+
+    let unsafe_pointer_diagnostic = |op: &str, location: &crate::parser::SourceLocation, context: &str| {
+        BorrowCheckDiagnostic {
+            severity: Severity::Error,
+            message: format!(
+                "unsafe pointer {} {}in function '{}': pointer operations require unsafe context",
+                op, context, function_name
+            ),
+            location: Location::from(location),
+            help: Some("wrap this operation in an `unsafe { ... }` block".to_string()),
+            notes: vec![],
+            labels: vec![],
+            function: Some(function_name.to_string()),
+            suggestion: None,
+            code: Some("RUSTYCPP-E0001"),
+        }
+    };
+
     match stmt {
         Statement::Assignment { rhs, location, .. } => {
             if let Some(op) = contains_pointer_operation(rhs) {
-                return Some(format!(
-                    "Unsafe pointer {} at line {}: pointer operations require unsafe context",
-                    op, location.line
-                ));
+                let mut diagnostic = unsafe_pointer_diagnostic(op, location, "");
+                diagnostic.suggestion = unsafe_wrap_suggestion(stmt, location);
+                return Some(diagnostic);
             }
         }
         Statement::VariableDecl(var) if var.is_pointer => {
             // Raw pointer declaration is allowed, but dereferencing isn't
             return None;
         }
-        Statement::FunctionCall { args, location, .. } => {
+        Statement::ReferenceBinding { target, location, .. } => {
+            if let Some(op) = contains_pointer_operation(target) {
+                // No textual fix offered, same as the `If` condition arm:
+                // `render_statement` doesn't know how to re-render a
+                // reference binding, so `unsafe_wrap_suggestion` would
+                // return `None` anyway.
+                return Some(unsafe_pointer_diagnostic(op, location, "in a reference binding "));
+            }
+        }
+        Statement::FunctionCall { name, args, location } => {
+            if let Some(op) = raw_alloc_operation(name) {
+                let mut diagnostic = unsafe_pointer_diagnostic(op, location, "");
+                diagnostic.suggestion = unsafe_wrap_suggestion(stmt, location);
+                return Some(diagnostic);
+            }
             for arg in args {
                 if let Some(op) = contains_pointer_operation(arg) {
-                    return Some(format!(
-                        "Unsafe pointer {} in function call at line {}: pointer operations require unsafe context",
-                        op, location.line
-                    ));
+                    let mut diagnostic = unsafe_pointer_diagnostic(op, location, "in function call ");
+                    diagnostic.suggestion = unsafe_wrap_suggestion(stmt, location);
+                    return Some(diagnostic);
                 }
             }
         }
         Statement::Return(Some(expr)) => {
             if let Some(op) = contains_pointer_operation(expr) {
-                return Some(format!(
-                    "Unsafe pointer {} in return statement: pointer operations require unsafe context",
-                    op
-                ));
+                let mut diagnostic = unsafe_pointer_diagnostic(op, fallback_location, "in return statement ");
+                diagnostic.suggestion = unsafe_wrap_suggestion(stmt, fallback_location);
+                return Some(diagnostic);
             }
         }
         Statement::If { condition, location, .. } => {
             if let Some(op) = contains_pointer_operation(condition) {
-                return Some(format!(
-                    "Unsafe pointer {} in condition at line {}: pointer operations require unsafe context", 
-                    op, location.line
-                ));
+                // Unlike the other arms, there's no textual fix to offer
+                // here: the pointer operation is embedded in the `if`'s
+                // condition, and wrapping just the condition in
+                // `unsafe { ... }` isn't valid C++ -- fixing this requires
+                // hoisting the dereference into a preceding statement,
+                // which is a restructuring, not a substitution.
+                return Some(unsafe_pointer_diagnostic(op, location, "in condition "));
             }
         }
         _ => {}
     }
-    
+
     None
 }
 
+/// `new`/`delete` aren't a distinct [`Expression`]/[`Statement`] variant in
+/// this parser -- they show up as an ordinary call named `"new"`/`"delete"`,
+/// so they're recognized by name here rather than by AST shape, the same
+/// way [`super::unsafe_propagation`]'s standard-function whitelist does.
+fn raw_alloc_operation(name: &str) -> Option<&'static str> {
+    match name {
+        "new" => Some("raw allocation"),
+        "delete" => Some("raw deallocation"),
+        _ => None,
+    }
+}
+
 fn contains_pointer_operation(expr: &Expression) -> Option<&'static str> {
     use crate::parser::Expression;
-    
+
     match expr {
         Expression::Dereference(_) => Some("dereference"),
         Expression::AddressOf(_) => {
             // Taking address is generally safe in Rust, but we can make it require unsafe too
             Some("address-of")
         }
-        Expression::FunctionCall { args, .. } => {
+        Expression::FunctionCall { name, args } => {
+            if let Some(op) = raw_alloc_operation(name) {
+                return Some(op);
+            }
             // Check arguments recursively
             for arg in args {
                 if let Some(op) = contains_pointer_operation(arg) {
@@ -94,15 +183,74 @@ fn contains_pointer_operation(expr: &Expression) -> Option<&'static str> {
             }
             contains_pointer_operation(right)
         }
+        Expression::Field { base, .. } => contains_pointer_operation(base),
         _ => None
     }
 }
 
+/// A best-effort "wrap this statement in `unsafe { ... }`" fix, for the
+/// statement kinds [`render_statement`] knows how to re-render as C++ text.
+/// `MaybeIncorrect` rather than `MachineApplicable` because the
+/// re-rendering doesn't preserve the original formatting (whitespace,
+/// comments, macro use) -- just its structure -- so it's a starting point
+/// for the author to apply, not something to rewrite the file with blindly.
+fn unsafe_wrap_suggestion(stmt: &Statement, location: &crate::parser::SourceLocation) -> Option<Suggestion> {
+    let rendered = render_statement(stmt)?;
+    Some(Suggestion {
+        span: Location::from(location),
+        replacement: format!("unsafe {{ {} }}", rendered),
+        applicability: Applicability::MaybeIncorrect,
+    })
+}
+
+/// Re-render a statement as C++ source text, for the handful of shapes
+/// [`check_parsed_statement_for_pointers`] can offer an `unsafe` wrap for.
+/// `pub(crate)` so [`super::unsafe_propagation`] can build the same kind of
+/// "wrap in `unsafe { ... }`" suggestion for an unsafe function call instead
+/// of re-rendering statements a second way.
+pub(crate) fn render_statement(stmt: &Statement) -> Option<String> {
+    match stmt {
+        Statement::Assignment { lhs, rhs, .. } => Some(format!("{} = {};", lhs, render_expression(rhs))),
+        Statement::FunctionCall { name, args, .. } => {
+            Some(format!("{}({});", name, render_args(args)))
+        }
+        Statement::Return(Some(expr)) => Some(format!("return {};", render_expression(expr))),
+        _ => None,
+    }
+}
+
+fn render_args(args: &[Expression]) -> String {
+    args.iter().map(render_expression).collect::<Vec<_>>().join(", ")
+}
+
+fn render_expression(expr: &Expression) -> String {
+    match expr {
+        Expression::Variable(name) => name.clone(),
+        Expression::Move(inner) => format!("std::move({})", render_expression(inner)),
+        Expression::Dereference(inner) => format!("*{}", render_expression(inner)),
+        Expression::AddressOf(inner) => format!("&{}", render_expression(inner)),
+        Expression::FunctionCall { name, args } => format!("{}({})", name, render_args(args)),
+        Expression::Literal(lit) => lit.clone(),
+        Expression::BinaryOp { left, op, right } => {
+            format!("{} {} {}", render_expression(left), op, render_expression(right))
+        }
+        Expression::Field { base, member } => format!("{}.{}", render_expression(base), member),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::parser::{Expression, Statement, SourceLocation, Variable};
-    
+
+    fn test_fn_location() -> SourceLocation {
+        SourceLocation {
+            file: "test.cpp".to_string(),
+            line: 1,
+            column: 1,
+        }
+    }
+
     #[test]
     fn test_detect_dereference() {
         let expr = Expression::Dereference(Box::new(Expression::Variable("ptr".to_string())));
@@ -133,9 +281,9 @@ mod tests {
             },
         };
         
-        let error = check_parsed_statement_for_pointers(&stmt);
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
         assert!(error.is_some());
-        assert!(error.unwrap().contains("dereference"));
+        assert!(error.unwrap().message.contains("dereference"));
     }
     
     #[test]
@@ -150,9 +298,9 @@ mod tests {
             },
         };
         
-        let error = check_parsed_statement_for_pointers(&stmt);
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
         assert!(error.is_some());
-        assert!(error.unwrap().contains("address-of"));
+        assert!(error.unwrap().message.contains("address-of"));
     }
     
     #[test]
@@ -169,11 +317,11 @@ mod tests {
             },
         };
         
-        let error = check_parsed_statement_for_pointers(&stmt);
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
         assert!(error.is_some());
-        let error_msg = error.unwrap();
-        assert!(error_msg.contains("function call"));
-        assert!(error_msg.contains("dereference"));
+        let diagnostic = error.unwrap();
+        assert!(diagnostic.message.contains("function call"));
+        assert!(diagnostic.message.contains("dereference"));
     }
     
     #[test]
@@ -206,6 +354,7 @@ mod tests {
             is_const: false,
             is_unique_ptr: false,
             is_shared_ptr: false,
+            is_union: false,
             location: SourceLocation {
                 file: "test.cpp".to_string(),
                 line: 5,
@@ -213,7 +362,126 @@ mod tests {
             },
         });
         
-        let error = check_parsed_statement_for_pointers(&stmt);
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
         assert!(error.is_none(), "Pointer declaration should be allowed");
     }
+
+    fn deref_assignment(line: u32) -> Statement {
+        Statement::Assignment {
+            lhs: "x".to_string(),
+            rhs: Expression::Dereference(Box::new(Expression::Variable("ptr".to_string()))),
+            location: SourceLocation { file: "test.cpp".to_string(), line, column: 5 },
+        }
+    }
+
+    fn test_function(body: Vec<Statement>) -> crate::parser::Function {
+        crate::parser::Function {
+            name: "test_fn".to_string(),
+            qualified_name: "test_fn".to_string(),
+            parameters: vec![],
+            return_type: "void".to_string(),
+            body,
+            location: test_fn_location(),
+        }
+    }
+
+    fn unsafe_block(statements: Vec<Statement>) -> Statement {
+        Statement::UnsafeBlock { statements, location: SourceLocation { file: "test.cpp".to_string(), line: 9, column: 5 } }
+    }
+
+    #[test]
+    fn test_dereference_inside_unsafe_block_is_allowed() {
+        let function = test_function(vec![unsafe_block(vec![deref_assignment(10)])]);
+
+        let diagnostics = check_parsed_function_for_pointers(&function);
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_dereference_outside_unsafe_block_is_still_rejected() {
+        let function = test_function(vec![
+            unsafe_block(vec![deref_assignment(10)]),
+            deref_assignment(11),
+        ]);
+
+        let diagnostics = check_parsed_function_for_pointers(&function);
+        assert_eq!(diagnostics.len(), 1, "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_nested_block_inside_unsafe_is_still_suppressed() {
+        let function = test_function(vec![
+            unsafe_block(vec![Statement::Block(vec![deref_assignment(10)])]),
+        ]);
+
+        let diagnostics = check_parsed_function_for_pointers(&function);
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_raw_new_outside_unsafe_block_is_rejected() {
+        let stmt = Statement::FunctionCall {
+            name: "new".to_string(),
+            args: vec![],
+            location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+        };
+
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
+        assert!(error.is_some());
+        assert!(error.unwrap().message.contains("raw allocation"));
+    }
+
+    #[test]
+    fn test_raw_delete_inside_unsafe_block_is_allowed() {
+        let function = test_function(vec![unsafe_block(vec![Statement::FunctionCall {
+            name: "delete".to_string(),
+            args: vec![],
+            location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+        }])]);
+
+        let diagnostics = check_parsed_function_for_pointers(&function);
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_pointer_diagnostic_carries_error_code() {
+        let error = check_parsed_statement_for_pointers(&deref_assignment(10), "test_fn", &test_fn_location());
+        assert_eq!(error.unwrap().code, Some("RUSTYCPP-E0001"));
+    }
+
+    #[test]
+    fn test_dereference_suggestion_wraps_statement_in_unsafe() {
+        let error = check_parsed_statement_for_pointers(&deref_assignment(10), "test_fn", &test_fn_location());
+        let suggestion = error.unwrap().suggestion.expect("should suggest an unsafe wrap");
+        assert_eq!(suggestion.replacement, "unsafe { x = *ptr; }");
+    }
+
+    #[test]
+    fn test_condition_pointer_operation_has_no_suggestion() {
+        // Wrapping just the condition expression in `unsafe { ... }` isn't
+        // valid C++, so this case is diagnosed but not auto-fixable.
+        let stmt = Statement::If {
+            condition: Expression::Dereference(Box::new(Expression::Variable("ptr".to_string()))),
+            then_branch: vec![],
+            else_branch: None,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 12, column: 5 },
+        };
+
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
+        assert!(error.unwrap().suggestion.is_none());
+    }
+
+    #[test]
+    fn test_reference_bound_to_dereference_is_flagged() {
+        let stmt = Statement::ReferenceBinding {
+            name: "r".to_string(),
+            target: Expression::Dereference(Box::new(Expression::Variable("ptr".to_string()))),
+            is_mutable: false,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 14, column: 5 },
+        };
+
+        let error = check_parsed_statement_for_pointers(&stmt, "test_fn", &test_fn_location());
+        assert!(error.is_some(), "binding a reference to a dereference should require unsafe context");
+        assert_eq!(error.unwrap().location.line, 14);
+    }
 }
\ No newline at end of file
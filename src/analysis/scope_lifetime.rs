@@ -369,8 +369,8 @@ fn check_call_lifetimes(
         // This would require mapping signature lifetimes to actual argument lifetimes
         tracker.add_constraint(LifetimeConstraint {
             kind: ConstraintKind::Outlives {
-                longer: bound.longer.clone(),
-                shorter: bound.shorter.clone(),
+                longer: bound.longer.to_string(),
+                shorter: bound.shorter.to_string(),
             },
             location: format!("call to {}", func_name),
         });
@@ -0,0 +1,309 @@
+use crate::diagnostics::{BorrowCheckDiagnostic, Location, Severity};
+use crate::parser::safety_annotations::SafetyContext;
+use crate::parser::{Expression, Function, SourceLocation, Statement};
+use std::collections::HashSet;
+
+/// Find `unsafe { ... }` blocks that don't carry their weight: either
+/// nothing inside them actually needs unsafe context, or they're nested
+/// directly inside another `unsafe` block that already covers them, or
+/// the enclosing function is itself `@unsafe` so the whole body -- and
+/// therefore every `unsafe { ... }` in it, including the outermost one --
+/// is already exempt from checking. All three are reported as warnings
+/// rather than errors -- they're noise, not unsoundness.
+pub fn check_unnecessary_unsafe_blocks(
+    function: &Function,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+) -> Vec<BorrowCheckDiagnostic> {
+    let already_unsafe = safety_context.function_overrides.iter().any(|(name, mode)| {
+        name == &function.name && *mode == crate::parser::safety_annotations::SafetyMode::Unsafe
+    });
+    let mut diagnostics = Vec::new();
+    let enclosing = if already_unsafe { Enclosing::WholeFunction } else { Enclosing::None };
+    find_unsafe_blocks(&function.body, &enclosing, &function.name, safety_context, known_safe_functions, &mut diagnostics);
+    diagnostics
+}
+
+/// What, if anything, already makes a nested `unsafe { ... }` redundant.
+/// Kept separate from a plain `Option<&SourceLocation>` because the
+/// function-level `@unsafe` case has no block of its own to point a label
+/// at -- there's no source span for "this entire function".
+enum Enclosing<'a> {
+    None,
+    WholeFunction,
+    Block(&'a SourceLocation),
+}
+
+fn find_unsafe_blocks(
+    statements: &[Statement],
+    enclosing_unsafe: &Enclosing,
+    function_name: &str,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+    diagnostics: &mut Vec<BorrowCheckDiagnostic>,
+) {
+    for stmt in statements {
+        match stmt {
+            Statement::UnsafeBlock { statements: inner, location } => {
+                match enclosing_unsafe {
+                    Enclosing::Block(enclosing) => diagnostics.push(redundant_nested_diagnostic(function_name, location, Some(enclosing))),
+                    Enclosing::WholeFunction => diagnostics.push(redundant_nested_diagnostic(function_name, location, None)),
+                    Enclosing::None => {
+                        if !any_statement_needs_unsafe(inner, safety_context, known_safe_functions) {
+                            diagnostics.push(unnecessary_unsafe_diagnostic(function_name, location));
+                        }
+                    }
+                }
+                let nested = Enclosing::Block(location);
+                find_unsafe_blocks(inner, &nested, function_name, safety_context, known_safe_functions, diagnostics);
+            }
+            Statement::Block(inner) => {
+                find_unsafe_blocks(inner, enclosing_unsafe, function_name, safety_context, known_safe_functions, diagnostics);
+            }
+            Statement::If { then_branch, else_branch, .. } => {
+                find_unsafe_blocks(then_branch, enclosing_unsafe, function_name, safety_context, known_safe_functions, diagnostics);
+                if let Some(else_branch) = else_branch {
+                    find_unsafe_blocks(else_branch, enclosing_unsafe, function_name, safety_context, known_safe_functions, diagnostics);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn any_statement_needs_unsafe(
+    statements: &[Statement],
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+) -> bool {
+    statements.iter().any(|stmt| statement_needs_unsafe(stmt, safety_context, known_safe_functions))
+}
+
+/// `new`/`delete` show up as an ordinary call named `"new"`/`"delete"` in
+/// this parser, same as [`super::pointer_safety::raw_alloc_operation`]
+/// recognizes them.
+fn is_raw_alloc(name: &str) -> bool {
+    matches!(name, "new" | "delete")
+}
+
+/// Whether `stmt`, anywhere inside it (including inside further-nested
+/// `unsafe` blocks -- their contents still need unsafe context even if
+/// their own wrapper is itself redundant), contains a pointer operation or
+/// a call to a function that isn't known-safe.
+fn statement_needs_unsafe(
+    stmt: &Statement,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+) -> bool {
+    match stmt {
+        Statement::Assignment { rhs, .. } => expr_needs_unsafe(rhs, safety_context, known_safe_functions),
+        Statement::FunctionCall { name, args, .. } => {
+            is_raw_alloc(name)
+                || !super::unsafe_propagation::is_function_safe(name, safety_context, known_safe_functions)
+                || args.iter().any(|arg| expr_needs_unsafe(arg, safety_context, known_safe_functions))
+        }
+        Statement::Return(Some(expr)) => expr_needs_unsafe(expr, safety_context, known_safe_functions),
+        Statement::If { condition, then_branch, else_branch, .. } => {
+            expr_needs_unsafe(condition, safety_context, known_safe_functions)
+                || any_statement_needs_unsafe(then_branch, safety_context, known_safe_functions)
+                || else_branch.as_ref().map_or(false, |branch| {
+                    any_statement_needs_unsafe(branch, safety_context, known_safe_functions)
+                })
+        }
+        Statement::Block(inner) => any_statement_needs_unsafe(inner, safety_context, known_safe_functions),
+        Statement::UnsafeBlock { statements, .. } => any_statement_needs_unsafe(statements, safety_context, known_safe_functions),
+        Statement::ReferenceBinding { target, .. } => expr_needs_unsafe(target, safety_context, known_safe_functions),
+        _ => false,
+    }
+}
+
+fn expr_needs_unsafe(
+    expr: &Expression,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+) -> bool {
+    match expr {
+        Expression::Dereference(_) | Expression::AddressOf(_) => true,
+        Expression::Move(inner) => expr_needs_unsafe(inner, safety_context, known_safe_functions),
+        Expression::FunctionCall { name, args } => {
+            is_raw_alloc(name)
+                || !super::unsafe_propagation::is_function_safe(name, safety_context, known_safe_functions)
+                || args.iter().any(|arg| expr_needs_unsafe(arg, safety_context, known_safe_functions))
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            expr_needs_unsafe(left, safety_context, known_safe_functions)
+                || expr_needs_unsafe(right, safety_context, known_safe_functions)
+        }
+        Expression::Field { base, .. } => expr_needs_unsafe(base, safety_context, known_safe_functions),
+        Expression::Variable(_) | Expression::Literal(_) => false,
+    }
+}
+
+fn unnecessary_unsafe_diagnostic(function_name: &str, location: &SourceLocation) -> BorrowCheckDiagnostic {
+    BorrowCheckDiagnostic {
+        severity: Severity::Warning,
+        message: format!(
+            "unnecessary `unsafe` block in function '{}': nothing inside it requires unsafe context",
+            function_name
+        ),
+        location: Location::from(location),
+        help: Some("remove the `unsafe { ... }` wrapper".to_string()),
+        notes: vec![],
+        labels: vec![],
+        function: Some(function_name.to_string()),
+        suggestion: None,
+        code: Some("RUSTYCPP-E0003"),
+    }
+}
+
+fn redundant_nested_diagnostic(
+    function_name: &str,
+    location: &SourceLocation,
+    enclosing: Option<&SourceLocation>,
+) -> BorrowCheckDiagnostic {
+    let (notes, labels) = match enclosing {
+        Some(enclosing) => (vec![], vec![(Location::from(enclosing), "the enclosing `unsafe` block starts here".to_string())]),
+        None => (vec![format!("function '{}' is declared `@unsafe`, so its entire body is already unsafe", function_name)], vec![]),
+    };
+    BorrowCheckDiagnostic {
+        severity: Severity::Warning,
+        message: format!(
+            "unnecessary unsafe block in function '{}': enclosing context is already unsafe",
+            function_name
+        ),
+        location: Location::from(location),
+        help: Some("remove this `unsafe { ... }`; the enclosing unsafe context already covers it".to_string()),
+        notes,
+        labels,
+        function: Some(function_name.to_string()),
+        suggestion: None,
+        code: Some("RUSTYCPP-E0004"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SourceLocation;
+
+    fn test_function(body: Vec<Statement>) -> Function {
+        Function {
+            name: "test_fn".to_string(),
+            qualified_name: "test_fn".to_string(),
+            parameters: vec![],
+            return_type: "void".to_string(),
+            body,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 1, column: 1 },
+        }
+    }
+
+    fn unsafe_block(statements: Vec<Statement>, line: u32) -> Statement {
+        Statement::UnsafeBlock { statements, location: SourceLocation { file: "test.cpp".to_string(), line, column: 5 } }
+    }
+
+    fn deref_assignment(line: u32) -> Statement {
+        Statement::Assignment {
+            lhs: "x".to_string(),
+            rhs: Expression::Dereference(Box::new(Expression::Variable("ptr".to_string()))),
+            location: SourceLocation { file: "test.cpp".to_string(), line, column: 5 },
+        }
+    }
+
+    #[test]
+    fn test_unsafe_block_with_dereference_is_not_flagged() {
+        let function = test_function(vec![unsafe_block(vec![deref_assignment(10)], 9)]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &SafetyContext::new(), &HashSet::new());
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_empty_unsafe_block_is_flagged_unnecessary() {
+        let function = test_function(vec![unsafe_block(vec![Statement::Return(None)], 9)]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &SafetyContext::new(), &HashSet::new());
+        assert_eq!(diagnostics.len(), 1, "got: {:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("unnecessary"));
+        assert_eq!(diagnostics[0].location.line, 9);
+    }
+
+    #[test]
+    fn test_nested_unsafe_block_is_flagged_redundant() {
+        let function = test_function(vec![unsafe_block(
+            vec![unsafe_block(vec![deref_assignment(11)], 10)],
+            9,
+        )]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &SafetyContext::new(), &HashSet::new());
+        assert_eq!(diagnostics.len(), 1, "got: {:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("enclosing context is already unsafe"));
+        assert_eq!(diagnostics[0].location.line, 10);
+        assert_eq!(diagnostics[0].labels.len(), 1, "got: {:?}", diagnostics[0].labels);
+        assert_eq!(diagnostics[0].labels[0].0.line, 9);
+        assert!(diagnostics[0].labels[0].1.contains("enclosing"));
+    }
+
+    #[test]
+    fn test_unsafe_block_in_unsafe_function_is_flagged_redundant() {
+        // The function itself is `@unsafe`, so its whole body -- including
+        // the outermost `unsafe { ... }` -- is already exempt from
+        // checking; that outer block is therefore dead markup too.
+        let mut safety_context = SafetyContext::new();
+        safety_context.function_overrides.push(("test_fn".to_string(), crate::parser::safety_annotations::SafetyMode::Unsafe));
+
+        let function = test_function(vec![unsafe_block(vec![deref_assignment(10)], 9)]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &safety_context, &HashSet::new());
+        assert_eq!(diagnostics.len(), 1, "got: {:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("enclosing context is already unsafe"));
+        assert_eq!(diagnostics[0].location.line, 9);
+        // No `unsafe { ... }` block marks the whole function `@unsafe`, so
+        // there's no span to label -- the explanation goes in a note instead.
+        assert!(diagnostics[0].labels.is_empty(), "got: {:?}", diagnostics[0].labels);
+        assert!(diagnostics[0].notes.iter().any(|n| n.contains("@unsafe")), "got: {:?}", diagnostics[0].notes);
+    }
+
+    #[test]
+    fn test_unsafe_block_inside_if_branch_of_unsafe_function_is_flagged_redundant() {
+        // Same "enclosing context is already unsafe" rule, but the
+        // redundant block sits inside an `if` branch rather than directly
+        // in the function body -- exercising `find_unsafe_blocks`'s `If`
+        // recursion together with the function-level exemption.
+        let mut safety_context = SafetyContext::new();
+        safety_context.function_overrides.push(("test_fn".to_string(), crate::parser::safety_annotations::SafetyMode::Unsafe));
+
+        let function = test_function(vec![Statement::If {
+            condition: Expression::Variable("cond".to_string()),
+            then_branch: vec![unsafe_block(vec![deref_assignment(11)], 10)],
+            else_branch: None,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 9, column: 5 },
+        }]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &safety_context, &HashSet::new());
+        assert_eq!(diagnostics.len(), 1, "got: {:?}", diagnostics);
+        assert!(diagnostics[0].message.contains("enclosing context is already unsafe"));
+        assert_eq!(diagnostics[0].location.line, 10);
+    }
+
+    #[test]
+    fn test_unsafe_block_wrapping_unknown_call_is_not_flagged() {
+        let function = test_function(vec![unsafe_block(
+            vec![Statement::FunctionCall {
+                name: "unknown_func".to_string(),
+                args: vec![],
+                location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+            }],
+            9,
+        )]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &SafetyContext::new(), &HashSet::new());
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_unsafe_block_wrapping_reference_binding_dereference_is_not_flagged() {
+        let reference_binding = Statement::ReferenceBinding {
+            name: "r".to_string(),
+            target: Expression::Dereference(Box::new(Expression::Variable("ptr".to_string()))),
+            is_mutable: false,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+        };
+        let function = test_function(vec![unsafe_block(vec![reference_binding], 9)]);
+        let diagnostics = check_unnecessary_unsafe_blocks(&function, &SafetyContext::new(), &HashSet::new());
+        assert!(diagnostics.is_empty(), "got: {:?}", diagnostics);
+    }
+}
@@ -0,0 +1,275 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+
+use regex::Regex;
+
+use crate::parser::safety_annotations::SafetyContext;
+use crate::parser::{CppAst, Expression, Function, Statement};
+
+use super::unsafe_propagation::is_function_safe;
+
+/// Per-function unsafe-surface tally produced by [`audit_unsafe_usage`].
+///
+/// Inspired by siderophile's AST walker: instead of a pass/fail result,
+/// this gives a triage list of where to focus annotation/hardening effort,
+/// ranked by how much unsafe surface area a function actually touches.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnsafeAuditEntry {
+    pub qualified_name: String,
+    pub file: String,
+    pub pointer_derefs: usize,
+    pub unsafe_casts: usize,
+    pub direct_unsafe_calls: usize,
+    pub transitive_unsafe_calls: usize,
+}
+
+impl UnsafeAuditEntry {
+    /// Combined score used to rank the report; each indicator counts once
+    /// regardless of kind, mirroring how `check_unsafe_propagation` treats
+    /// any unannotated call as equally in need of review.
+    pub fn score(&self) -> usize {
+        self.pointer_derefs + self.unsafe_casts + self.direct_unsafe_calls + self.transitive_unsafe_calls
+    }
+}
+
+/// Walk every function in `ast`, tally unsafe indicators (raw pointer
+/// dereferences and address-of operations, reinterpret/C-style casts, calls
+/// into functions not known to be safe, and transitive reachability of
+/// unsafe callees), and return them sorted
+/// most-unsafe first.
+///
+/// Test files (matched by the usual `test_`/`_test`/`tests/` naming
+/// conventions) are skipped unless `include_tests` is set.
+pub fn audit_unsafe_usage(
+    ast: &CppAst,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+    include_tests: bool,
+) -> Vec<UnsafeAuditEntry> {
+    let functions: Vec<&Function> = ast
+        .functions
+        .iter()
+        .filter(|function| include_tests || !is_test_file(&function.location.file))
+        .collect();
+
+    let cast_counts = count_casts_per_function(&functions);
+    let call_graph = build_call_graph(&functions);
+
+    let mut entries: Vec<UnsafeAuditEntry> = functions
+        .iter()
+        .map(|function| {
+            let pointer_derefs = function
+                .body
+                .iter()
+                .map(count_pointer_operations_in_statement)
+                .sum();
+
+            let callees = call_graph.get(&function.name).cloned().unwrap_or_default();
+            let direct_unsafe_calls = callees
+                .iter()
+                .filter(|callee| !is_function_safe(callee, safety_context, known_safe_functions))
+                .count();
+            let transitive_unsafe_calls =
+                reachable_unsafe_functions(&function.name, &call_graph, safety_context, known_safe_functions).len();
+
+            UnsafeAuditEntry {
+                qualified_name: function.qualified_name.clone(),
+                file: function.location.file.clone(),
+                pointer_derefs,
+                unsafe_casts: cast_counts.get(&function.qualified_name).copied().unwrap_or(0),
+                direct_unsafe_calls,
+                transitive_unsafe_calls,
+            }
+        })
+        .collect();
+
+    entries.sort_by(|a, b| {
+        b.score()
+            .cmp(&a.score())
+            .then_with(|| a.qualified_name.cmp(&b.qualified_name))
+    });
+    entries
+}
+
+fn is_test_file(file: &str) -> bool {
+    let lower = file.to_lowercase();
+    lower.contains("/test/") || lower.contains("/tests/") || lower.contains("test_") || lower.contains("_test")
+}
+
+/// Build a call graph keyed by (unqualified) function name, the same
+/// identifier `check_unsafe_propagation`'s callee checks already key on,
+/// so the two stay in lockstep.
+fn build_call_graph(functions: &[&Function]) -> HashMap<String, Vec<String>> {
+    let mut graph = HashMap::new();
+    for function in functions {
+        let mut callees = Vec::new();
+        for stmt in &function.body {
+            collect_calls_in_statement(stmt, &mut callees);
+        }
+        graph.insert(function.name.clone(), callees);
+    }
+    graph
+}
+
+fn collect_calls_in_statement(stmt: &Statement, callees: &mut Vec<String>) {
+    match stmt {
+        Statement::FunctionCall { name, args, .. } => {
+            callees.push(name.clone());
+            for arg in args {
+                collect_calls_in_expression(arg, callees);
+            }
+        }
+        Statement::Assignment { rhs, .. } => collect_calls_in_expression(rhs, callees),
+        Statement::ReferenceBinding { target, .. } => collect_calls_in_expression(target, callees),
+        Statement::Return(Some(expr)) => collect_calls_in_expression(expr, callees),
+        Statement::If { condition, then_branch, else_branch, .. } => {
+            collect_calls_in_expression(condition, callees);
+            for branch_stmt in then_branch {
+                collect_calls_in_statement(branch_stmt, callees);
+            }
+            if let Some(else_stmts) = else_branch {
+                for branch_stmt in else_stmts {
+                    collect_calls_in_statement(branch_stmt, callees);
+                }
+            }
+        }
+        Statement::Block(statements) | Statement::UnsafeBlock { statements, .. } => {
+            for block_stmt in statements {
+                collect_calls_in_statement(block_stmt, callees);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn collect_calls_in_expression(expr: &Expression, callees: &mut Vec<String>) {
+    match expr {
+        Expression::FunctionCall { name, args } => {
+            callees.push(name.clone());
+            for arg in args {
+                collect_calls_in_expression(arg, callees);
+            }
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            collect_calls_in_expression(left, callees);
+            collect_calls_in_expression(right, callees);
+        }
+        Expression::Move(inner) | Expression::Dereference(inner) | Expression::AddressOf(inner) => {
+            collect_calls_in_expression(inner, callees);
+        }
+        Expression::Field { base, .. } => collect_calls_in_expression(base, callees),
+        _ => {}
+    }
+}
+
+/// Walk the call graph from `start`, collecting every unsafe function
+/// transitively reachable through it -- including through intermediate
+/// `@safe` wrappers, since a safe-looking call chain can still bottom out
+/// in unsafe code several calls down.
+fn reachable_unsafe_functions(
+    start: &str,
+    call_graph: &HashMap<String, Vec<String>>,
+    safety_context: &SafetyContext,
+    known_safe_functions: &HashSet<String>,
+) -> HashSet<String> {
+    let mut unsafe_reachable = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut queue: Vec<String> = call_graph.get(start).cloned().unwrap_or_default();
+
+    while let Some(callee) = queue.pop() {
+        if !seen.insert(callee.clone()) {
+            continue;
+        }
+        if !is_function_safe(&callee, safety_context, known_safe_functions) {
+            unsafe_reachable.insert(callee.clone());
+        }
+        if let Some(next_callees) = call_graph.get(&callee) {
+            queue.extend(next_callees.iter().cloned());
+        }
+    }
+
+    unsafe_reachable
+}
+
+fn count_pointer_operations_in_statement(stmt: &Statement) -> usize {
+    match stmt {
+        Statement::Assignment { rhs, .. } => count_pointer_operations_in_expression(rhs),
+        Statement::ReferenceBinding { target, .. } => count_pointer_operations_in_expression(target),
+        Statement::FunctionCall { args, .. } => {
+            args.iter().map(count_pointer_operations_in_expression).sum()
+        }
+        Statement::Return(Some(expr)) => count_pointer_operations_in_expression(expr),
+        Statement::If { condition, then_branch, else_branch, .. } => {
+            count_pointer_operations_in_expression(condition)
+                + then_branch.iter().map(count_pointer_operations_in_statement).sum::<usize>()
+                + else_branch
+                    .as_ref()
+                    .map(|stmts| stmts.iter().map(count_pointer_operations_in_statement).sum())
+                    .unwrap_or(0)
+        }
+        // An `unsafe { ... }` block is still part of the function's unsafe
+        // surface for this tally -- unlike the pass/fail checks, the audit
+        // wants to know how much raw-pointer work a function does at all,
+        // annotated or not, so it walks into it the same as a plain `Block`.
+        Statement::Block(statements) | Statement::UnsafeBlock { statements, .. } => {
+            statements.iter().map(count_pointer_operations_in_statement).sum()
+        }
+        _ => 0,
+    }
+}
+
+fn count_pointer_operations_in_expression(expr: &Expression) -> usize {
+    match expr {
+        Expression::Dereference(inner) | Expression::AddressOf(inner) => {
+            1 + count_pointer_operations_in_expression(inner)
+        }
+        Expression::FunctionCall { args, .. } => {
+            args.iter().map(count_pointer_operations_in_expression).sum()
+        }
+        Expression::BinaryOp { left, right, .. } => {
+            count_pointer_operations_in_expression(left) + count_pointer_operations_in_expression(right)
+        }
+        Expression::Move(inner) => count_pointer_operations_in_expression(inner),
+        Expression::Field { base, .. } => count_pointer_operations_in_expression(base),
+        _ => 0,
+    }
+}
+
+/// Count `reinterpret_cast<...>` and C-style pointer casts (`(Type*)expr`)
+/// per function. The custom AST doesn't model cast expressions, so this
+/// reads the source text directly and attributes each match to whichever
+/// function's declaration line is the closest one at or before it.
+fn count_casts_per_function(functions: &[&Function]) -> HashMap<String, usize> {
+    let reinterpret_cast = Regex::new(r"reinterpret_cast\s*<").expect("valid regex");
+    let c_style_cast = Regex::new(r"\(\s*[A-Za-z_][A-Za-z0-9_:]*\s*\*+\s*\)\s*[A-Za-z_(]").expect("valid regex");
+
+    let mut by_file: HashMap<&str, Vec<&Function>> = HashMap::new();
+    for function in functions {
+        by_file.entry(function.location.file.as_str()).or_default().push(*function);
+    }
+
+    let mut counts = HashMap::new();
+    for (file, funcs_in_file) in by_file {
+        let Ok(source) = fs::read_to_string(file) else {
+            continue;
+        };
+
+        let mut starts: Vec<(u32, &str)> = funcs_in_file
+            .iter()
+            .map(|f| (f.location.line, f.qualified_name.as_str()))
+            .collect();
+        starts.sort_by_key(|(line, _)| *line);
+
+        for (line_idx, line) in source.lines().enumerate() {
+            let line_no = (line_idx + 1) as u32;
+            if !(reinterpret_cast.is_match(line) || c_style_cast.is_match(line)) {
+                continue;
+            }
+            if let Some((_, name)) = starts.iter().rev().find(|(start, _)| *start <= line_no) {
+                *counts.entry(name.to_string()).or_insert(0) += 1;
+            }
+        }
+    }
+
+    counts
+}
@@ -1,9 +1,10 @@
-use crate::parser::{Function, Statement, Expression};
+use crate::diagnostics::{Applicability, BorrowCheckDiagnostic, Location, Severity, Suggestion};
+use crate::parser::{Function, Statement, Expression, SourceLocation};
 use crate::parser::safety_annotations::SafetyContext;
 use std::collections::HashSet;
 
 /// Check for unsafe propagation in safe functions
-/// 
+///
 /// In safe code, the following require explicit @unsafe annotation:
 /// 1. Calling functions not marked as @safe
 /// 2. Using types/structs not marked as @safe
@@ -12,89 +13,172 @@ pub fn check_unsafe_propagation(
     function: &Function,
     safety_context: &SafetyContext,
     known_safe_functions: &HashSet<String>,
-) -> Vec<String> {
-    let mut errors = Vec::new();
-    
+) -> Vec<BorrowCheckDiagnostic> {
+    // A function annotated `@unsafe` starts life already inside unsafe
+    // context, the same as `check_unnecessary_unsafe_blocks`'s own
+    // `already_unsafe` -- its whole body is licensed, including any
+    // `unsafe { ... }` blocks nested in it, so callers don't also have to
+    // gate this check behind `should_check_function` themselves.
+    let in_unsafe = if safety_context.should_check_function(&function.name) { 0 } else { 1 };
+
+    let mut diagnostics = Vec::new();
+
     // Check each statement in the function
     for stmt in &function.body {
-        if let Some(error) = check_statement_for_unsafe_calls(stmt, safety_context, known_safe_functions) {
-            errors.push(format!("In function '{}': {}", function.name, error));
+        if let Some(diagnostic) = check_statement_for_unsafe_calls(
+            stmt,
+            safety_context,
+            known_safe_functions,
+            &function.name,
+            &function.location,
+            in_unsafe,
+        ) {
+            diagnostics.push(diagnostic);
         }
     }
-    
-    errors
+
+    diagnostics
+}
+
+fn unsafe_call_diagnostic(
+    callee: &str,
+    function_name: &str,
+    location: &SourceLocation,
+    context: &str,
+    stmt: &Statement,
+) -> BorrowCheckDiagnostic {
+    BorrowCheckDiagnostic {
+        severity: Severity::Error,
+        message: format!(
+            "calling unsafe function '{}' {}in function '{}' requires unsafe context",
+            callee, context, function_name
+        ),
+        location: Location::from(location),
+        help: Some(format!(
+            "wrap this call to `{}` in an `unsafe {{ ... }}` block, or mark it `@safe`",
+            callee
+        )),
+        notes: vec![],
+        labels: vec![],
+        function: Some(function_name.to_string()),
+        suggestion: unsafe_wrap_suggestion(stmt, location),
+        code: Some("RUSTYCPP-E0002"),
+    }
+}
+
+/// A best-effort "wrap this statement in `unsafe { ... }`" fix, reusing
+/// [`super::pointer_safety::render_statement`]'s re-rendering instead of
+/// duplicating it -- same `MaybeIncorrect` applicability, for the same
+/// reason: the re-rendered text doesn't preserve the original formatting.
+fn unsafe_wrap_suggestion(stmt: &Statement, location: &SourceLocation) -> Option<Suggestion> {
+    let rendered = super::pointer_safety::render_statement(stmt)?;
+    Some(Suggestion {
+        span: Location::from(location),
+        replacement: format!("unsafe {{ {} }}", rendered),
+        applicability: Applicability::MaybeIncorrect,
+    })
 }
 
+/// Walk `stmt` looking for a call to a non-`@safe` function, skipping the
+/// check (but still recursing, so a further-nested `unsafe { ... }` is
+/// still found and doesn't change anything) whenever `in_unsafe` is
+/// nonzero -- an effect-context depth the same way `pointer_safety`'s
+/// `check_statements_for_pointers` carries one, incremented only by
+/// entering an `UnsafeBlock` and never decremented inside it, since an
+/// `unsafe` block nested in another is still unsafe.
 fn check_statement_for_unsafe_calls(
     stmt: &Statement,
     safety_context: &SafetyContext,
     known_safe_functions: &HashSet<String>,
-) -> Option<String> {
+    function_name: &str,
+    fallback_location: &SourceLocation,
+    in_unsafe: usize,
+) -> Option<BorrowCheckDiagnostic> {
     use crate::parser::Statement;
-    
-    match stmt {
-        Statement::FunctionCall { name, location, .. } => {
-            // Check if the called function is safe
-            if !is_function_safe(name, safety_context, known_safe_functions) {
-                return Some(format!(
-                    "Calling unsafe function '{}' at line {} requires unsafe context",
-                    name, location.line
-                ));
+
+    if in_unsafe == 0 {
+        match stmt {
+            Statement::FunctionCall { name, location, .. } => {
+                if !is_function_safe(name, safety_context, known_safe_functions) {
+                    return Some(unsafe_call_diagnostic(name, function_name, location, "", stmt));
+                }
             }
-        }
-        Statement::Assignment { rhs, location, .. } => {
-            // Check for function calls in the right-hand side
-            if let Some(unsafe_func) = find_unsafe_function_call(rhs, safety_context, known_safe_functions) {
-                return Some(format!(
-                    "Calling unsafe function '{}' at line {} requires unsafe context",
-                    unsafe_func, location.line
-                ));
+            Statement::Assignment { rhs, location, .. } => {
+                if let Some(unsafe_func) = find_unsafe_function_call(rhs, safety_context, known_safe_functions) {
+                    return Some(unsafe_call_diagnostic(&unsafe_func, function_name, location, "", stmt));
+                }
             }
-        }
-        Statement::Return(Some(expr)) => {
-            // Check for function calls in return expression
-            if let Some(unsafe_func) = find_unsafe_function_call(expr, safety_context, known_safe_functions) {
-                return Some(format!(
-                    "Calling unsafe function '{}' in return statement requires unsafe context",
-                    unsafe_func
-                ));
+            Statement::Return(Some(expr)) => {
+                if let Some(unsafe_func) = find_unsafe_function_call(expr, safety_context, known_safe_functions) {
+                    return Some(unsafe_call_diagnostic(
+                        &unsafe_func,
+                        function_name,
+                        fallback_location,
+                        "in a return statement ",
+                        stmt,
+                    ));
+                }
             }
-        }
-        Statement::If { condition, then_branch, else_branch, location } => {
-            // Check condition
-            if let Some(unsafe_func) = find_unsafe_function_call(condition, safety_context, known_safe_functions) {
-                return Some(format!(
-                    "Calling unsafe function '{}' in condition at line {} requires unsafe context",
-                    unsafe_func, location.line
-                ));
+            Statement::If { condition, location, .. } => {
+                if let Some(unsafe_func) = find_unsafe_function_call(condition, safety_context, known_safe_functions) {
+                    // No textual fix offered here, same as `pointer_safety`:
+                    // wrapping just the condition in `unsafe { ... }` isn't
+                    // valid C++, and `render_statement` returns `None` for
+                    // `If` anyway, so `unsafe_call_diagnostic` naturally
+                    // leaves `suggestion` empty.
+                    return Some(unsafe_call_diagnostic(&unsafe_func, function_name, location, "in a condition ", stmt));
+                }
             }
-            
-            // Recursively check branches
+            Statement::ReferenceBinding { target, location, .. } => {
+                if let Some(unsafe_func) = find_unsafe_function_call(target, safety_context, known_safe_functions) {
+                    return Some(unsafe_call_diagnostic(&unsafe_func, function_name, location, "in a reference binding ", stmt));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match stmt {
+        Statement::If { then_branch, else_branch, .. } => {
             for branch_stmt in then_branch {
-                if let Some(error) = check_statement_for_unsafe_calls(branch_stmt, safety_context, known_safe_functions) {
-                    return Some(error);
+                if let Some(diagnostic) = check_statement_for_unsafe_calls(
+                    branch_stmt, safety_context, known_safe_functions, function_name, fallback_location, in_unsafe,
+                ) {
+                    return Some(diagnostic);
                 }
             }
-            
+
             if let Some(else_stmts) = else_branch {
                 for branch_stmt in else_stmts {
-                    if let Some(error) = check_statement_for_unsafe_calls(branch_stmt, safety_context, known_safe_functions) {
-                        return Some(error);
+                    if let Some(diagnostic) = check_statement_for_unsafe_calls(
+                        branch_stmt, safety_context, known_safe_functions, function_name, fallback_location, in_unsafe,
+                    ) {
+                        return Some(diagnostic);
                     }
                 }
             }
         }
         Statement::Block(statements) => {
-            // Check all statements in the block
             for block_stmt in statements {
-                if let Some(error) = check_statement_for_unsafe_calls(block_stmt, safety_context, known_safe_functions) {
-                    return Some(error);
+                if let Some(diagnostic) = check_statement_for_unsafe_calls(
+                    block_stmt, safety_context, known_safe_functions, function_name, fallback_location, in_unsafe,
+                ) {
+                    return Some(diagnostic);
+                }
+            }
+        }
+        Statement::UnsafeBlock { statements, .. } => {
+            for block_stmt in statements {
+                if let Some(diagnostic) = check_statement_for_unsafe_calls(
+                    block_stmt, safety_context, known_safe_functions, function_name, fallback_location, in_unsafe + 1,
+                ) {
+                    return Some(diagnostic);
                 }
             }
         }
         _ => {}
     }
-    
+
     None
 }
 
@@ -134,28 +218,41 @@ fn find_unsafe_function_call(
                 return Some(unsafe_func);
             }
         }
+        Expression::Field { base, .. } => {
+            if let Some(unsafe_func) = find_unsafe_function_call(base, safety_context, known_safe_functions) {
+                return Some(unsafe_func);
+            }
+        }
         _ => {}
     }
     
     None
 }
 
-fn is_function_safe(
+pub(crate) fn is_function_safe(
     func_name: &str,
     safety_context: &SafetyContext,
     known_safe_functions: &HashSet<String>,
 ) -> bool {
+    use crate::parser::safety_annotations::SafetyPolicy;
+
     // Check if it's explicitly marked as safe
     // Only functions with explicit @safe annotation are considered safe
     if known_safe_functions.contains(func_name) {
         return true;
     }
-    
-    // Check for standard library functions we consider safe
-    if is_standard_safe_function(func_name) {
+
+    // Under `AllowlistedSafe`, the project's own call lists replace this
+    // crate's hardcoded (and admittedly wrong -- see `is_standard_safe_function`)
+    // guess entirely, so a project can force `gets`/`strcpy` unsafe.
+    if safety_context.policy == Some(SafetyPolicy::AllowlistedSafe) {
+        if safety_context.allowlist.permits(func_name) {
+            return true;
+        }
+    } else if is_standard_safe_function(func_name) {
         return true;
     }
-    
+
     // Check if it's explicitly marked as unsafe - still unsafe
     for (name, mode) in &safety_context.function_overrides {
         if name == func_name {
@@ -186,7 +283,15 @@ fn is_standard_safe_function(func_name: &str) -> bool {
 mod tests {
     use super::*;
     use crate::parser::{Statement, Expression, SourceLocation};
-    
+
+    fn test_fn_location() -> SourceLocation {
+        SourceLocation {
+            file: "test.cpp".to_string(),
+            line: 1,
+            column: 1,
+        }
+    }
+
     #[test]
     fn test_detect_unsafe_function_call() {
         let stmt = Statement::FunctionCall {
@@ -202,13 +307,15 @@ mod tests {
         let safety_context = SafetyContext::new();
         let known_safe = HashSet::new();
         
-        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe);
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
         assert!(error.is_some());
-        let error_msg = error.unwrap();
-        assert!(error_msg.contains("unknown_func"));
-        assert!(error_msg.contains("unsafe"));
+        let diagnostic = error.unwrap();
+        assert!(diagnostic.message.contains("unknown_func"));
+        assert!(diagnostic.message.contains("unsafe"));
+        let suggestion = diagnostic.suggestion.expect("a FunctionCall statement should get a wrap suggestion");
+        assert_eq!(suggestion.replacement, "unsafe { unknown_func(); }");
     }
-    
+
     #[test]
     fn test_safe_function_allowed() {
         let stmt = Statement::FunctionCall {
@@ -224,7 +331,7 @@ mod tests {
         let safety_context = SafetyContext::new();
         let known_safe = HashSet::new();
         
-        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe);
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
         assert!(error.is_none(), "printf should be considered safe");
     }
     
@@ -244,7 +351,7 @@ mod tests {
         let mut known_safe = HashSet::new();
         known_safe.insert("my_safe_func".to_string());
         
-        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe);
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
         assert!(error.is_none(), "Known safe function should be allowed");
     }
     
@@ -266,9 +373,88 @@ mod tests {
         let safety_context = SafetyContext::new();
         let known_safe = HashSet::new();
         
-        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe);
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
         assert!(error.is_some());
-        let error_msg = error.unwrap();
-        assert!(error_msg.contains("unsafe_func"));
+        let diagnostic = error.unwrap();
+        assert!(diagnostic.message.contains("unsafe_func"));
+    }
+
+    #[test]
+    fn test_unsafe_call_in_reference_binding_target() {
+        let stmt = Statement::ReferenceBinding {
+            name: "r".to_string(),
+            target: Expression::FunctionCall { name: "unsafe_func".to_string(), args: vec![] },
+            is_mutable: false,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 15, column: 5 },
+        };
+
+        let safety_context = SafetyContext::new();
+        let known_safe = HashSet::new();
+
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
+        assert!(error.is_some(), "an unsafe call in a reference binding's target should be caught");
+    }
+
+    #[test]
+    fn test_unsafe_call_inside_unsafe_block_is_allowed() {
+        let stmt = Statement::UnsafeBlock {
+            statements: vec![Statement::FunctionCall {
+                name: "unknown_func".to_string(),
+                args: vec![],
+                location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+            }],
+            location: SourceLocation { file: "test.cpp".to_string(), line: 9, column: 5 },
+        };
+
+        let safety_context = SafetyContext::new();
+        let known_safe = HashSet::new();
+
+        let error = check_statement_for_unsafe_calls(&stmt, &safety_context, &known_safe, "test_fn", &test_fn_location(), 0);
+        assert!(error.is_none(), "calls inside an unsafe block should not be flagged");
+    }
+
+    #[test]
+    fn test_unsafe_function_licenses_its_whole_body() {
+        let function = Function {
+            name: "test_fn".to_string(),
+            qualified_name: "test_fn".to_string(),
+            parameters: vec![],
+            return_type: "void".to_string(),
+            body: vec![Statement::FunctionCall {
+                name: "unknown_func".to_string(),
+                args: vec![],
+                location: SourceLocation { file: "test.cpp".to_string(), line: 10, column: 5 },
+            }],
+            location: test_fn_location(),
+        };
+
+        let mut safety_context = SafetyContext::new();
+        safety_context.function_overrides.push(("test_fn".to_string(), crate::parser::safety_annotations::SafetyMode::Unsafe));
+        let known_safe = HashSet::new();
+
+        let diagnostics = check_unsafe_propagation(&function, &safety_context, &known_safe);
+        assert!(diagnostics.is_empty(), "an `@unsafe` function's body should not be checked: {:?}", diagnostics);
+    }
+
+    #[test]
+    fn test_allowlisted_safe_policy_consults_allowlist_instead_of_builtin_list() {
+        use crate::parser::safety_annotations::{CallAllowlist, SafetyPolicy};
+
+        let mut safety_context = SafetyContext::new();
+        safety_context.policy = Some(SafetyPolicy::AllowlistedSafe);
+        safety_context.allowlist = CallAllowlist {
+            safe_functions: vec!["my_wrapper".to_string()],
+            safe_namespaces: vec![],
+            banned_functions: vec!["strcpy".to_string()],
+        };
+        let known_safe = HashSet::new();
+
+        // Allowlisted by name.
+        assert!(is_function_safe("my_wrapper", &safety_context, &known_safe));
+        // `strcpy` is on the hardcoded whitelist, but the allowlist policy
+        // bans it explicitly, so it must stay unsafe.
+        assert!(!is_function_safe("strcpy", &safety_context, &known_safe));
+        // Not mentioned anywhere: unsafe under this policy's closed world.
+        assert!(!is_function_safe("some_other_func", &safety_context, &known_safe));
     }
 }
\ No newline at end of file
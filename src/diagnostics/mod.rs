@@ -1,5 +1,8 @@
 use colored::*;
+use serde_json::{json, Value};
+use std::collections::HashMap;
 use std::fmt;
+use std::fs;
 
 #[derive(Debug, Clone)]
 pub struct BorrowCheckDiagnostic {
@@ -8,6 +11,45 @@ pub struct BorrowCheckDiagnostic {
     pub location: Location,
     pub help: Option<String>,
     pub notes: Vec<String>,
+    /// Secondary labeled spans, e.g. ("borrow starts here", "value dropped here"),
+    /// rendered as additional underlined snippets alongside the primary one.
+    pub labels: Vec<(Location, String)>,
+    /// The enclosing function, when the analysis that produced this
+    /// diagnostic is function-scoped (most are; whole-program checks like
+    /// the lifetime solver leave this `None`).
+    pub function: Option<String>,
+    /// A machine-applicable (or tentative) fix, rustc-suggestion-style.
+    pub suggestion: Option<Suggestion>,
+    /// A stable, greppable error code (e.g. `"RUSTYCPP-E0001"`) identifying
+    /// the class of violation, for checks precise enough to have settled on
+    /// one. Most of the older checks predate this and leave it `None`.
+    pub code: Option<&'static str>,
+}
+
+/// A proposed edit attached to a diagnostic: replace the text at `span`
+/// with `replacement`. Mirrors rustc's `Suggestion` / `Applicability`.
+#[derive(Debug, Clone)]
+pub struct Suggestion {
+    pub span: Location,
+    pub replacement: String,
+    pub applicability: Applicability,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Applicability {
+    /// Safe to apply automatically without user review.
+    MachineApplicable,
+    /// Plausible, but the author should double-check before applying.
+    MaybeIncorrect,
+}
+
+impl Applicability {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Applicability::MachineApplicable => "MachineApplicable",
+            Applicability::MaybeIncorrect => "MaybeIncorrect",
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -18,6 +60,16 @@ pub enum Severity {
     Note,
 }
 
+impl Severity {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Location {
     pub file: String,
@@ -35,12 +87,10 @@ impl fmt::Display for BorrowCheckDiagnostic {
             Severity::Note => "note".blue().bold(),
         };
         
-        writeln!(
-            f,
-            "{}: {}",
-            severity_str,
-            self.message.bold()
-        )?;
+        match self.code {
+            Some(code) => writeln!(f, "{}[{}]: {}", severity_str, code, self.message.bold())?,
+            None => writeln!(f, "{}: {}", severity_str, self.message.bold())?,
+        }
         
         writeln!(
             f,
@@ -54,15 +104,208 @@ impl fmt::Display for BorrowCheckDiagnostic {
         if let Some(ref help) = self.help {
             writeln!(f, "{}: {}", "help".green().bold(), help)?;
         }
-        
+
+        if let Some(ref suggestion) = self.suggestion {
+            writeln!(
+                f,
+                "{}: replace with `{}`",
+                "help".green().bold(),
+                suggestion.replacement
+            )?;
+        }
+
         for note in &self.notes {
             writeln!(f, "{}: {}", "note".blue(), note)?;
         }
-        
+
         Ok(())
     }
 }
 
+impl From<&crate::parser::SourceLocation> for Location {
+    fn from(location: &crate::parser::SourceLocation) -> Self {
+        Location {
+            file: location.file.clone(),
+            line: location.line,
+            column: location.column,
+            span: None,
+        }
+    }
+}
+
+impl BorrowCheckDiagnostic {
+    /// Render this diagnostic `annotate-snippets`-style: the plain
+    /// rustc-like header (from `Display`), followed by the offending
+    /// source line(s) with `^^^` carets under the primary span and any
+    /// secondary labels, each under their own source line.
+    pub fn render_with_source(&self, source: &str) -> String {
+        let mut out = format!("{}", self);
+
+        if let Some(snippet) = render_span(source, &self.location, None) {
+            out.push_str(&snippet);
+        }
+
+        for (location, label) in &self.labels {
+            if let Some(snippet) = render_span(source, location, Some(label.as_str())) {
+                out.push_str(&snippet);
+            }
+        }
+
+        out
+    }
+
+    /// Serialize this diagnostic the way `--error-format=json` would: a flat
+    /// object with a `spans` array (primary span first, then each labeled
+    /// secondary span) so editors/CI can consume it without re-parsing text.
+    pub fn to_json(&self) -> Value {
+        let mut spans = vec![span_json(&self.location, true, None)];
+        spans.extend(
+            self.labels
+                .iter()
+                .map(|(location, label)| span_json(location, false, Some(label.as_str()))),
+        );
+
+        json!({
+            "level": self.severity.as_str(),
+            "kind": self.kind(),
+            "code": self.code,
+            "message": self.message,
+            "symbol": self.symbol(),
+            "function": self.function,
+            "file": self.location.file,
+            "spans": spans,
+            "help": self.help,
+            "notes": self.notes,
+            "suggested_replacement": self.suggestion.as_ref().map(|s| s.replacement.clone()),
+            "suggestion_applicability": self.suggestion.as_ref().map(|s| s.applicability.as_str()),
+            "span": self.suggestion.as_ref().map(|s| span_json(&s.span, true, None)),
+        })
+    }
+
+    /// A stable, greppable category for `--format json` consumers, coarser
+    /// than `code` (which only the newer pointer/unsafe checks set) and
+    /// read off of the message text for everything else -- so the older
+    /// checks that still report free-form strings get a usable tag without
+    /// having to be rewritten to carry one of their own.
+    pub fn kind(&self) -> &'static str {
+        let m = &self.message;
+
+        if m.contains("no `@lifetime` annotation") {
+            "missing_lifetime_annotation"
+        } else if m.contains("unnecessary") && m.contains("unsafe") {
+            "unnecessary_unsafe"
+        } else if m.contains("unnecessary") {
+            "unnecessary_borrow"
+        } else if m.contains("dereference") {
+            "pointer_deref"
+        } else if m.contains("address-of") {
+            "address_of"
+        } else if m.contains("raw allocation") || m.contains("raw deallocation") {
+            "raw_alloc"
+        } else if m.contains("unsafe function") {
+            "unsafe_call"
+        } else if m.contains("moved") {
+            "use_after_move"
+        } else if m.contains("mutable more than once") || m.contains("already mutably borrowed") {
+            "double_mutable_borrow"
+        } else if m.contains("already immutably borrowed") {
+            "mutable_while_borrowed"
+        } else if m.contains("const reference") {
+            "const_violation"
+        } else if m.contains("lifetime") || m.contains("outlive") {
+            "lifetime_error"
+        } else {
+            "other"
+        }
+    }
+
+    /// The offending symbol name, best-effort: most diagnostic messages in
+    /// this checker name their variable/function in a pair of quotes
+    /// (`'ptr'` or `` `ptr` ``), so this just lifts the first one out
+    /// instead of asking every call site to also thread the name through as
+    /// its own field. A message with no quoted name yields `None`.
+    pub fn symbol(&self) -> Option<&str> {
+        let bytes = self.message.as_bytes();
+        let start = bytes.iter().position(|&b| b == b'\'' || b == b'`')? + 1;
+        let end = self.message[start..].find(|c| c == '\'' || c == '`')? + start;
+        Some(&self.message[start..end])
+    }
+}
+
+fn span_json(location: &Location, is_primary: bool, label: Option<&str>) -> Value {
+    let width = location.span.map(|(start, end)| end.saturating_sub(start).max(1));
+    json!({
+        "file": location.file,
+        "line_start": location.line,
+        "column_start": location.column,
+        "line_end": location.line,
+        "column_end": width.map(|w| location.column + w as u32).unwrap_or(location.column),
+        "byte_start": location.span.map(|(start, _)| start),
+        "byte_end": location.span.map(|(_, end)| end),
+        "is_primary": is_primary,
+        "label": label,
+    })
+}
+
+/// Render a single source line with a caret underline beneath `location`'s
+/// column (and span width, if known), optionally followed by a label.
+fn render_span(source: &str, location: &Location, label: Option<&str>) -> Option<String> {
+    let line_index = location.line.checked_sub(1)? as usize;
+    let line = source.lines().nth(line_index)?;
+
+    let column = location.column.max(1) as usize;
+    let width = location
+        .span
+        .map(|(start, end)| end.saturating_sub(start).max(1))
+        .unwrap_or(1);
+
+    let gutter = format!("{} | ", location.line);
+    let padding = " ".repeat(gutter.len() + column.saturating_sub(1));
+    let carets = "^".repeat(width);
+
+    let mut rendered = format!("{}{}\n{}{}", gutter, line, padding, carets.red());
+    if let Some(label) = label {
+        rendered.push(' ');
+        rendered.push_str(label);
+    }
+    rendered.push('\n');
+
+    Some(rendered)
+}
+
+/// Caches file contents keyed by path so rendering many diagnostics against
+/// the same file only reads it once.
+#[derive(Debug, Default)]
+pub struct SourceMap {
+    files: HashMap<String, String>,
+}
+
+impl SourceMap {
+    pub fn new() -> Self {
+        Self {
+            files: HashMap::new(),
+        }
+    }
+
+    fn source_for(&mut self, path: &str) -> Option<&str> {
+        if !self.files.contains_key(path) {
+            let contents = fs::read_to_string(path).ok()?;
+            self.files.insert(path.to_string(), contents);
+        }
+        self.files.get(path).map(String::as_str)
+    }
+
+    /// Render a diagnostic with source context if its file can be read,
+    /// falling back to the plain header when it can't (e.g. a synthetic
+    /// location with no file on disk).
+    pub fn render(&mut self, diagnostic: &BorrowCheckDiagnostic) -> String {
+        match self.source_for(&diagnostic.location.file) {
+            Some(source) => diagnostic.render_with_source(source),
+            None => format!("{}", diagnostic),
+        }
+    }
+}
+
 #[allow(dead_code)]
 pub fn format_use_after_move(var_name: &str, location: Location) -> BorrowCheckDiagnostic {
     BorrowCheckDiagnostic {
@@ -76,6 +319,10 @@ pub fn format_use_after_move(var_name: &str, location: Location) -> BorrowCheckD
             format!("value `{}` was moved previously", var_name),
             "once a value is moved, it cannot be used again".to_string(),
         ],
+        labels: vec![],
+        function: None,
+        suggestion: None,
+        code: None,
     }
 }
 
@@ -89,6 +336,10 @@ pub fn format_double_borrow(var_name: &str, location: Location) -> BorrowCheckDi
         notes: vec![
             "only one mutable borrow is allowed at a time".to_string(),
         ],
+        labels: vec![],
+        function: None,
+        suggestion: None,
+        code: None,
     }
 }
 
@@ -100,6 +351,10 @@ pub fn format_lifetime_error(message: String, location: Location) -> BorrowCheck
         location,
         help: Some("consider adjusting the lifetime annotations".to_string()),
         notes: vec![],
+        labels: vec![],
+        function: None,
+        suggestion: None,
+        code: None,
     }
 }
 
@@ -120,6 +375,10 @@ mod tests {
             },
             help: Some("Try this instead".to_string()),
             notes: vec!["Note 1".to_string()],
+            labels: vec![],
+            function: None,
+            suggestion: None,
+        code: None,
         };
         
         assert!(matches!(diag.severity, Severity::Error));
@@ -196,11 +455,85 @@ mod tests {
             },
             help: None,
             notes: vec![],
+            labels: vec![],
+            function: None,
+            suggestion: None,
+        code: None,
         };
-        
+
         let output = format!("{}", diag);
         assert!(output.contains("error"));
         assert!(output.contains("Test error message"));
         assert!(output.contains("example.cpp:5:10"));
     }
+
+    #[test]
+    fn test_render_with_source_shows_carets() {
+        let diag = BorrowCheckDiagnostic {
+            severity: Severity::Error,
+            message: "use of moved value: `ptr`".to_string(),
+            location: Location {
+                file: "example.cpp".to_string(),
+                line: 2,
+                column: 5,
+                span: Some((0, 3)),
+            },
+            help: None,
+            notes: vec![],
+            labels: vec![],
+            function: None,
+            suggestion: None,
+        code: None,
+        };
+
+        let source = "int main() {\n    ptr->run();\n}\n";
+        let rendered = diag.render_with_source(source);
+
+        assert!(rendered.contains("ptr->run();"));
+        assert!(rendered.contains("^^^"));
+    }
+
+    fn diagnostic_with_message(message: &str) -> BorrowCheckDiagnostic {
+        BorrowCheckDiagnostic {
+            severity: Severity::Error,
+            message: message.to_string(),
+            location: Location { file: "test.cpp".to_string(), line: 1, column: 1, span: None },
+            help: None,
+            notes: vec![],
+            labels: vec![],
+            function: None,
+            suggestion: None,
+            code: None,
+        }
+    }
+
+    #[test]
+    fn test_kind_classifies_use_after_move() {
+        let diag = diagnostic_with_message("Use after move: variable 'ptr' has already been moved");
+        assert_eq!(diag.kind(), "use_after_move");
+    }
+
+    #[test]
+    fn test_kind_classifies_double_mutable_borrow() {
+        let diag = diagnostic_with_message("cannot borrow 'x' as mutable more than once");
+        assert_eq!(diag.kind(), "double_mutable_borrow");
+    }
+
+    #[test]
+    fn test_kind_falls_back_to_other_for_unrecognized_message() {
+        let diag = diagnostic_with_message("something went sideways");
+        assert_eq!(diag.kind(), "other");
+    }
+
+    #[test]
+    fn test_symbol_extracts_first_quoted_name() {
+        let diag = diagnostic_with_message("Use after move: variable 'ptr' has already been moved");
+        assert_eq!(diag.symbol(), Some("ptr"));
+    }
+
+    #[test]
+    fn test_symbol_is_none_without_quotes() {
+        let diag = diagnostic_with_message("lifetime constraints are unsatisfiable");
+        assert_eq!(diag.symbol(), None);
+    }
 }
\ No newline at end of file
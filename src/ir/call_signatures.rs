@@ -0,0 +1,199 @@
+//! Per-parameter ownership effect of calling a function.
+//!
+//! `convert_statement` used to record a call's argument expressions and
+//! drop their ownership effect on the floor -- passing `x` to `f(x)` looked
+//! no different whether `f` took `x` by value, by `unique_ptr`, by `&`, or
+//! by `const&`. A [`SignatureTable`], built once from every [`Function`] in
+//! the translation unit before any call site is lowered, answers that
+//! question from the parameters the parser already records
+//! (`is_unique_ptr`/`is_reference`/`is_const`), so a call site can emit the
+//! same `Move`/`Borrow` IR it would for an explicit `std::move` or
+//! `ReferenceBinding`.
+
+use crate::parser::{Function, Variable};
+use std::collections::HashMap;
+
+/// What passing an argument to a given parameter does to the caller's place.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParamEffect {
+    /// A by-value `unique_ptr` parameter -- consumes the caller's place,
+    /// same as an explicit `std::move(...)` argument.
+    Consume,
+    /// A non-`const` reference parameter -- borrows mutably for the
+    /// call's duration.
+    MutableBorrow,
+    /// A `const` reference parameter, or anything passed by value that
+    /// isn't a `unique_ptr` -- borrows immutably for the call's duration.
+    /// Also the conservative default for a callee with no known signature.
+    SharedBorrow,
+}
+
+impl ParamEffect {
+    fn of(param: &Variable) -> Self {
+        if param.is_unique_ptr && !param.is_reference {
+            ParamEffect::Consume
+        } else if param.is_reference && !param.is_const {
+            ParamEffect::MutableBorrow
+        } else {
+            ParamEffect::SharedBorrow
+        }
+    }
+}
+
+/// One function's calling convention: the effect of each parameter, in
+/// declaration order.
+#[derive(Debug, Clone, Default)]
+pub struct CallSignature {
+    param_effects: Vec<ParamEffect>,
+}
+
+impl CallSignature {
+    fn from_function(function: &Function) -> Self {
+        CallSignature {
+            param_effects: function.parameters.iter().map(ParamEffect::of).collect(),
+        }
+    }
+
+    /// The effect of the argument at `index`, or the conservative
+    /// shared-borrow default if the signature has no parameter there (a
+    /// variadic call, or an arity mismatch the parser didn't catch).
+    pub fn effect_at(&self, index: usize) -> ParamEffect {
+        self.param_effects.get(index).copied().unwrap_or(ParamEffect::SharedBorrow)
+    }
+}
+
+/// Every callable signature in a translation unit, keyed by name and arity,
+/// with a name-only fallback for a function that has a single overload.
+#[derive(Debug, Clone, Default)]
+pub struct SignatureTable {
+    by_name_and_arity: HashMap<(String, usize), CallSignature>,
+    /// A name with exactly one overload resolves here even when the call
+    /// site's argument count doesn't match it exactly (default arguments,
+    /// or a call the parser under-counted) -- still a better guess than
+    /// falling all the way back to the unknown-callee default.
+    sole_overload_by_name: HashMap<String, CallSignature>,
+}
+
+impl SignatureTable {
+    pub fn build(functions: &[Function]) -> Self {
+        let mut overload_count: HashMap<&str, usize> = HashMap::new();
+        for function in functions {
+            *overload_count.entry(function.name.as_str()).or_insert(0) += 1;
+        }
+
+        let mut by_name_and_arity = HashMap::new();
+        let mut sole_overload_by_name = HashMap::new();
+        for function in functions {
+            let signature = CallSignature::from_function(function);
+            if overload_count.get(function.name.as_str()) == Some(&1) {
+                sole_overload_by_name.insert(function.name.clone(), signature.clone());
+            }
+            by_name_and_arity.insert((function.name.clone(), function.parameters.len()), signature);
+        }
+
+        SignatureTable { by_name_and_arity, sole_overload_by_name }
+    }
+
+    /// The signature to use for a call to `name` with `arg_count`
+    /// arguments: an exact name+arity match first, then the sole-overload
+    /// fallback, then `None` for an unknown callee -- callers treat that
+    /// conservatively as every argument taking a shared borrow.
+    pub fn lookup(&self, name: &str, arg_count: usize) -> Option<&CallSignature> {
+        self.by_name_and_arity
+            .get(&(name.to_string(), arg_count))
+            .or_else(|| self.sole_overload_by_name.get(name))
+    }
+
+    /// [`CallSignature::effect_at`], falling back to the conservative
+    /// shared-borrow default when `name` doesn't resolve to a known
+    /// signature at all.
+    pub fn effect_of(&self, name: &str, arg_count: usize, index: usize) -> ParamEffect {
+        self.lookup(name, arg_count).map(|sig| sig.effect_at(index)).unwrap_or(ParamEffect::SharedBorrow)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SourceLocation;
+
+    fn test_location() -> SourceLocation {
+        SourceLocation { file: "test.cpp".to_string(), line: 1, column: 1 }
+    }
+
+    fn param(name: &str, is_reference: bool, is_const: bool, is_unique_ptr: bool) -> Variable {
+        Variable {
+            name: name.to_string(),
+            type_name: "T".to_string(),
+            is_reference,
+            is_pointer: false,
+            is_const,
+            is_unique_ptr,
+            is_shared_ptr: false,
+            is_union: false,
+            location: test_location(),
+        }
+    }
+
+    fn function(name: &str, parameters: Vec<Variable>) -> Function {
+        Function {
+            name: name.to_string(),
+            qualified_name: name.to_string(),
+            parameters,
+            return_type: "void".to_string(),
+            body: vec![],
+            location: test_location(),
+        }
+    }
+
+    #[test]
+    fn by_value_unique_ptr_param_consumes() {
+        let table = SignatureTable::build(&[function("f", vec![param("p", false, false, true)])]);
+        assert_eq!(table.effect_of("f", 1, 0), ParamEffect::Consume);
+    }
+
+    #[test]
+    fn non_const_reference_param_is_mutable_borrow() {
+        let table = SignatureTable::build(&[function("f", vec![param("p", true, false, false)])]);
+        assert_eq!(table.effect_of("f", 1, 0), ParamEffect::MutableBorrow);
+    }
+
+    #[test]
+    fn const_reference_param_is_shared_borrow() {
+        let table = SignatureTable::build(&[function("f", vec![param("p", true, true, false)])]);
+        assert_eq!(table.effect_of("f", 1, 0), ParamEffect::SharedBorrow);
+    }
+
+    #[test]
+    fn plain_by_value_param_is_shared_borrow() {
+        let table = SignatureTable::build(&[function("f", vec![param("p", false, false, false)])]);
+        assert_eq!(table.effect_of("f", 1, 0), ParamEffect::SharedBorrow);
+    }
+
+    #[test]
+    fn unknown_callee_defaults_to_shared_borrow() {
+        let table = SignatureTable::build(&[]);
+        assert_eq!(table.effect_of("nonexistent", 1, 0), ParamEffect::SharedBorrow);
+    }
+
+    #[test]
+    fn overloads_resolve_by_arity() {
+        let table = SignatureTable::build(&[
+            function("f", vec![param("p", true, false, false)]),
+            function("f", vec![param("p", true, false, false), param("q", true, true, false)]),
+        ]);
+        assert_eq!(table.effect_of("f", 1, 0), ParamEffect::MutableBorrow);
+        assert_eq!(table.effect_of("f", 2, 1), ParamEffect::SharedBorrow);
+    }
+
+    #[test]
+    fn arity_mismatch_against_multiple_overloads_falls_back_to_shared_borrow() {
+        let table = SignatureTable::build(&[
+            function("f", vec![param("p", true, false, false)]),
+            function("f", vec![param("p", true, false, false), param("q", true, false, false)]),
+        ]);
+        // Neither overload has 3 parameters, and there's more than one
+        // overload, so there's no sole-overload fallback to use either.
+        assert_eq!(table.effect_of("f", 3, 0), ParamEffect::SharedBorrow);
+    }
+}
@@ -2,6 +2,9 @@ use crate::parser::CppAst;
 use petgraph::graph::{DiGraph, NodeIndex};
 use std::collections::HashMap;
 
+mod call_signatures;
+use call_signatures::{ParamEffect, SignatureTable};
+
 #[derive(Debug, Clone)]
 pub struct IrProgram {
     pub functions: Vec<IrFunction>,
@@ -37,6 +40,12 @@ pub enum VariableType {
     UniquePtr(String),
     SharedPtr(String),
     Raw(String),
+    /// A C++ `union` -- unlike a `struct`, every field shares the same
+    /// storage, so a borrow checker has to treat any two of its fields as
+    /// aliasing instead of the disjoint-sibling-paths treatment every
+    /// other field projection gets (see `analysis::OwnershipTracker`'s
+    /// place-overlap check).
+    Union(String),
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +54,12 @@ pub enum OwnershipState {
     Owned,
     Borrowed(BorrowKind),
     Moved,
+    /// Moved on some, but not all, of the control-flow paths that reach
+    /// this point -- e.g. one arm of an `if` moved it, or a loop body that
+    /// hasn't necessarily run yet. Distinct from `Moved`: a definite move
+    /// is a hard error on every subsequent use, while this is only a
+    /// conditional one, since the path that didn't move it is still live.
+    MaybeMoved,
     Uninitialized,
 }
 
@@ -53,6 +68,14 @@ pub enum OwnershipState {
 pub enum BorrowKind {
     Immutable,
     Mutable,
+    /// A mutable loan that starts out behaving like a shared borrow -- its
+    /// *reservation* -- and only becomes exclusive once a matching
+    /// `IrStatement::Activate` marks the point it's actually written
+    /// through. Lets a receiver-mutating call like `v.push(v.len())`
+    /// typecheck: `v`'s mutable loan is reserved before the argument list
+    /// runs, and evaluating `v.len()` in that window is just another
+    /// shared read, not a conflict.
+    TwoPhaseMutable,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -99,6 +122,16 @@ pub enum IrStatement {
         value: Option<String>,
     },
     Drop(String),
+    /// A read of these variables with no assignment of its own -- what a
+    /// loop/if condition or a loop increment lowers to, so the checker
+    /// counts them as uses without having to invent a fake assignment.
+    Read(Vec<String>),
+    /// The point a `BorrowKind::TwoPhaseMutable` loan on `reference`
+    /// actually starts writing through it, turning its reservation into
+    /// an exclusive borrow -- see `BorrowKind::TwoPhaseMutable`.
+    Activate {
+        reference: String,
+    },
     // Scope markers for tracking when blocks begin/end
     EnterScope,
     ExitScope,
@@ -139,41 +172,58 @@ pub enum OwnershipEdge {
 pub fn build_ir(ast: CppAst) -> Result<IrProgram, String> {
     let mut functions = Vec::new();
     let ownership_graph = DiGraph::new();
-    
+
+    // Built once from every function in the translation unit, before any
+    // call site is lowered, so `convert_statement` can look up a callee's
+    // parameter ownership without re-deriving it per call (and so a
+    // forward reference to a function defined later in the file still
+    // resolves).
+    let signatures = SignatureTable::build(&ast.functions);
+
     for func in ast.functions {
-        let ir_func = convert_function(&func)?;
+        let ir_func = convert_function(&func, &signatures)?;
         functions.push(ir_func);
     }
-    
+
     Ok(IrProgram {
         functions,
         ownership_graph,
     })
 }
 
-fn convert_function(func: &crate::parser::Function) -> Result<IrFunction, String> {
+fn convert_function(func: &crate::parser::Function, signatures: &SignatureTable) -> Result<IrFunction, String> {
     let mut cfg = DiGraph::new();
     let mut variables = HashMap::new();
-    
-    // Create entry block and convert statements
-    let mut statements = Vec::new();
-    for stmt in &func.body {
-        if let Some(ir_stmts) = convert_statement(stmt, &mut variables)? {
-            statements.extend(ir_stmts);
-        }
-    }
-    
-    let entry_block = BasicBlock {
-        id: 0,
-        statements,
-        terminator: None,
-    };
-    
-    let _entry_node = cfg.add_node(entry_block);
-    
+
+    // Scope depth at the point each `VariableDecl` is lowered, starting at
+    // 1 for the function's own root scope (matches
+    // `analysis::OwnershipTracker::new`'s single initial scope frame) and
+    // tracking every `EnterScope`/`ExitScope` pair lowering sees along the
+    // way -- this is what lets `VariableInfo::lifetime` record where a
+    // local actually lives instead of only the flat function-wide map.
+    let mut depth: usize = 1;
+
+    // A monotonically increasing id handed out once per call site, purely
+    // so `lower_call_args` can mint a synthetic borrower name that's unique
+    // to *this* call -- without it, two calls passing the same variable by
+    // reference (`mutate(x); mutate(x);`) would mint the identical
+    // `_call_borrow_x` name for both, and the analysis keys non-lexical
+    // liveness and conflict detection on that name: the second loan would
+    // silently clobber the first's `last_use` instead of the two being
+    // tracked (and expired) independently. See chunk15-5's review fix.
+    let mut call_site: usize = 0;
+
+    let entry_node = cfg.add_node(new_block(&cfg));
+    lower_statements(&func.body, &mut cfg, &mut variables, entry_node, &mut depth, signatures, &mut call_site)?;
+
     // Process parameters
     for param in &func.parameters {
-        let (var_type, ownership) = if param.is_unique_ptr {
+        let (var_type, ownership) = if param.is_union {
+            // A union parameter's fields alias regardless of whether it's
+            // passed by reference or by value, so it's classified ahead of
+            // the reference/unique_ptr checks below.
+            (VariableType::Union(param.type_name.clone()), OwnershipState::Owned)
+        } else if param.is_unique_ptr {
             (VariableType::UniquePtr(param.type_name.clone()), OwnershipState::Owned)
         } else if param.is_reference {
             if param.is_const {
@@ -205,15 +255,192 @@ fn convert_function(func: &crate::parser::Function) -> Result<IrFunction, String
     })
 }
 
+fn new_block(cfg: &ControlFlowGraph) -> BasicBlock {
+    BasicBlock {
+        id: cfg.node_count(),
+        statements: Vec::new(),
+        terminator: None,
+    }
+}
+
+/// Lower a straight-line run of parser statements into `cfg`, appending to
+/// `current` and splicing in real blocks (with real edges) wherever a loop
+/// appears, instead of flattening everything -- loop included -- into a
+/// single block with inline `EnterLoop`/`ExitLoop` markers. A loop becomes
+/// three blocks: a header that control reaches before the first iteration
+/// and after every subsequent one, a body that the header can fall through
+/// into, and an exit that the header can fall through to instead; the body
+/// closes the loop with a back edge to the header. This gives the
+/// ownership dataflow (see `analysis::check_function`) real predecessor
+/// edges to join over, so a loop's fixed point falls out of the graph
+/// instead of a "run the body twice" heuristic.
+///
+/// Returns the block execution falls through to once every statement in
+/// `stmts` has been lowered.
+fn lower_statements(
+    stmts: &[crate::parser::Statement],
+    cfg: &mut ControlFlowGraph,
+    variables: &mut HashMap<String, VariableInfo>,
+    mut current: NodeIndex,
+    depth: &mut usize,
+    signatures: &SignatureTable,
+    call_site: &mut usize,
+) -> Result<NodeIndex, String> {
+    use crate::parser::Statement;
+
+    let mut index = 0;
+    while index < stmts.len() {
+        match &stmts[index] {
+            Statement::EnterLoop => {
+                let body_end = matching_exit_loop(stmts, index + 1)?;
+                let body_stmts = &stmts[index + 1..body_end];
+
+                let header = cfg.add_node(new_block(cfg));
+                cfg.add_edge(current, header, ());
+
+                let body_start = cfg.add_node(new_block(cfg));
+                cfg.add_edge(header, body_start, ());
+                let body_end_node = lower_statements(body_stmts, cfg, variables, body_start, depth, signatures, call_site)?;
+                cfg.add_edge(body_end_node, header, ());
+
+                let after_loop = cfg.add_node(new_block(cfg));
+                cfg.add_edge(header, after_loop, ());
+
+                current = after_loop;
+                index = body_end + 1; // past the matching ExitLoop
+            }
+            other => {
+                if let Some(ir_stmts) = convert_statement(other, variables, depth, signatures, call_site)? {
+                    cfg[current].statements.extend(ir_stmts);
+                }
+                index += 1;
+            }
+        }
+    }
+
+    Ok(current)
+}
+
+/// Find the index of the `ExitLoop` matching the `EnterLoop` whose body
+/// starts at `from`, accounting for nested loops.
+fn matching_exit_loop(stmts: &[crate::parser::Statement], from: usize) -> Result<usize, String> {
+    use crate::parser::Statement;
+
+    let mut depth = 1;
+    let mut index = from;
+    while index < stmts.len() {
+        match &stmts[index] {
+            Statement::EnterLoop => depth += 1,
+            Statement::ExitLoop => {
+                depth -= 1;
+                if depth == 0 {
+                    return Ok(index);
+                }
+            }
+            _ => {}
+        }
+        index += 1;
+    }
+
+    Err("EnterLoop with no matching ExitLoop".to_string())
+}
+
+/// Lower a call's arguments against `name`'s [`SignatureTable`] entry,
+/// turning each plain-variable argument into the move or scoped borrow its
+/// matching parameter actually performs: a [`ParamEffect::Consume`]
+/// parameter (or an explicit `std::move(...)` argument, regardless of what
+/// the callee's signature says) marks the variable moved before the call,
+/// same as an ordinary move; a [`ParamEffect::MutableBorrow`] or
+/// [`ParamEffect::SharedBorrow`] parameter reserves a borrow under a
+/// synthetic name that appears nowhere else, so its last use -- and so its
+/// non-lexical liveness -- is the call itself, instead of lingering for
+/// the rest of the enclosing scope the way a named reference binding does.
+/// `call_site` is a per-function counter bumped once per call so that
+/// borrower name is also unique to this call: two calls passing the same
+/// variable by reference mint two distinct synthetic names, so the loans
+/// are tracked -- and expire -- independently instead of one colliding
+/// with and clobbering the other.
+/// Returns the statements to run immediately before the `CallExpr`, and
+/// the names the `CallExpr` itself should list as arguments.
+fn lower_call_args(
+    name: &str,
+    args: &[crate::parser::Expression],
+    signatures: &SignatureTable,
+    call_site: &mut usize,
+) -> (Vec<IrStatement>, Vec<String>) {
+    let mut statements = Vec::new();
+    let mut arg_names = Vec::new();
+    let site = *call_site;
+    *call_site += 1;
+
+    for (index, arg) in args.iter().enumerate() {
+        match arg {
+            crate::parser::Expression::Variable(var) => match signatures.effect_of(name, args.len(), index) {
+                ParamEffect::Consume => {
+                    statements.push(IrStatement::Move {
+                        from: var.clone(),
+                        to: format!("_temp_move_{}", var),
+                    });
+                    arg_names.push(var.clone());
+                }
+                ParamEffect::MutableBorrow => {
+                    let borrower = format!("_call_borrow_{}_{}", site, var);
+                    statements.push(IrStatement::Borrow { from: var.clone(), to: borrower.clone(), kind: BorrowKind::Mutable });
+                    arg_names.push(borrower);
+                }
+                ParamEffect::SharedBorrow => {
+                    let borrower = format!("_call_borrow_{}_{}", site, var);
+                    statements.push(IrStatement::Borrow { from: var.clone(), to: borrower.clone(), kind: BorrowKind::Immutable });
+                    arg_names.push(borrower);
+                }
+            },
+            crate::parser::Expression::Move(inner) => {
+                if let Some(place) = place_of(inner) {
+                    statements.push(IrStatement::Move {
+                        from: place.clone(),
+                        to: format!("_temp_move_{}", place),
+                    });
+                    arg_names.push(place);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (statements, arg_names)
+}
+
+/// Render an expression as the dotted-path place string the borrow checker
+/// keys its state on (see `analysis::move_paths::Place`), if it names a
+/// place at all. `s.a` becomes `"s.a"`; anything that isn't a chain of
+/// field projections off a variable -- a call result, a literal -- has no
+/// place to borrow from.
+fn place_of(expr: &crate::parser::Expression) -> Option<String> {
+    use crate::parser::Expression;
+
+    match expr {
+        Expression::Variable(name) => Some(name.clone()),
+        Expression::Field { base, member } => Some(format!("{}.{}", place_of(base)?, member)),
+        _ => None,
+    }
+}
+
 fn convert_statement(
     stmt: &crate::parser::Statement,
     variables: &mut HashMap<String, VariableInfo>,
+    depth: &mut usize,
+    signatures: &SignatureTable,
+    call_site: &mut usize,
 ) -> Result<Option<Vec<IrStatement>>, String> {
     use crate::parser::Statement;
-    
+
     match stmt {
         Statement::VariableDecl(var) => {
-            let (var_type, ownership) = if var.is_unique_ptr {
+            let (var_type, ownership) = if var.is_union {
+                // Same priority as the parameter classification above: a
+                // union local's fields alias no matter how it's declared.
+                (VariableType::Union(var.type_name.clone()), OwnershipState::Owned)
+            } else if var.is_unique_ptr {
                 (VariableType::UniquePtr(var.type_name.clone()), OwnershipState::Owned)
             } else if var.is_reference {
                 if var.is_const {
@@ -226,27 +453,42 @@ fn convert_statement(
             } else {
                 (VariableType::Owned(var.type_name.clone()), OwnershipState::Owned)
             };
-            
+
             variables.insert(
                 var.name.clone(),
                 VariableInfo {
                     name: var.name.clone(),
                     ty: var_type,
                     ownership,
-                    lifetime: None,
+                    // Where this local actually lives, for the borrow
+                    // checker's region analysis (see
+                    // `analysis::OwnershipTracker::region_of`) -- the
+                    // function's flat `variables` map otherwise has no way
+                    // to tell a root-scope local from one declared deep
+                    // inside an `if`/loop body.
+                    lifetime: Some(Lifetime {
+                        name: var.name.clone(),
+                        scope_start: *depth,
+                        scope_end: *depth,
+                    }),
                 },
             );
             Ok(None)
         }
         Statement::ReferenceBinding { name, target, is_mutable, .. } => {
-            // Convert to a borrow statement
-            if let crate::parser::Expression::Variable(target_var) = target {
+            // Convert to a borrow statement. `target` may name a whole
+            // variable (`int& r = x;`) or a field projection of one
+            // (`int& r = s.a;`); either way `place_of` renders it down to
+            // the dotted-path string the checker's `Place` parser expects,
+            // so `&mut s.a` and `&s.b` are tracked as distinct places
+            // instead of both collapsing onto the whole of `s`.
+            if let Some(target_place) = place_of(target) {
                 let kind = if *is_mutable {
                     BorrowKind::Mutable
                 } else {
                     BorrowKind::Immutable
                 };
-                
+
                 // Update the reference variable's ownership state and type
                 if let Some(var_info) = variables.get_mut(name) {
                     var_info.ownership = OwnershipState::Borrowed(kind.clone());
@@ -261,9 +503,9 @@ fn convert_statement(
                         }
                     }
                 }
-                
+
                 Ok(Some(vec![IrStatement::Borrow {
-                    from: target_var.clone(),
+                    from: target_place,
                     to: name.clone(),
                     kind,
                 }]))
@@ -311,41 +553,29 @@ fn convert_statement(
                             from: var.clone(),
                             to: lhs.clone(),
                         }]))
+                    } else if let Some(place) = place_of(inner) {
+                        // A field move (`std::move(s.a)`) has no whole-variable
+                        // type to transfer onto `lhs`, but the source field
+                        // still needs to be recorded as moved at its own
+                        // dotted place.
+                        Ok(Some(vec![IrStatement::Move {
+                            from: place,
+                            to: lhs.clone(),
+                        }]))
                     } else {
                         // Handle nested expressions if needed
                         Ok(None)
                     }
                 }
                 crate::parser::Expression::FunctionCall { name, args } => {
-                    // Convert function call arguments, handling moves
-                    let mut statements = Vec::new();
-                    let mut arg_names = Vec::new();
-                    
-                    for arg in args {
-                        match arg {
-                            crate::parser::Expression::Variable(var) => {
-                                arg_names.push(var.clone());
-                            }
-                            crate::parser::Expression::Move(inner) => {
-                                if let crate::parser::Expression::Variable(var) = inner.as_ref() {
-                                    // Mark as moved before the call
-                                    statements.push(IrStatement::Move {
-                                        from: var.clone(),
-                                        to: format!("_temp_move_{}", var),
-                                    });
-                                    arg_names.push(var.clone());
-                                }
-                            }
-                            _ => {}
-                        }
-                    }
-                    
+                    let (mut statements, arg_names) = lower_call_args(name, args, signatures, call_site);
+
                     statements.push(IrStatement::CallExpr {
                         func: name.clone(),
                         args: arg_names,
                         result: Some(lhs.clone()),
                     });
-                    
+
                     Ok(Some(statements))
                 }
                 _ => Ok(None)
@@ -353,36 +583,14 @@ fn convert_statement(
         }
         Statement::FunctionCall { name, args, .. } => {
             // Standalone function call (no assignment)
-            let mut statements = Vec::new();
-            let mut arg_names = Vec::new();
-            
-            // Process arguments, looking for std::move
-            for arg in args {
-                match arg {
-                    crate::parser::Expression::Variable(var) => {
-                        arg_names.push(var.clone());
-                    }
-                    crate::parser::Expression::Move(inner) => {
-                        // Handle std::move in function arguments
-                        if let crate::parser::Expression::Variable(var) = inner.as_ref() {
-                            // First mark the variable as moved
-                            statements.push(IrStatement::Move {
-                                from: var.clone(),
-                                to: format!("_moved_{}", var), // Temporary marker
-                            });
-                            arg_names.push(var.clone());
-                        }
-                    }
-                    _ => {}
-                }
-            }
-            
+            let (mut statements, arg_names) = lower_call_args(name, args, signatures, call_site);
+
             statements.push(IrStatement::CallExpr {
                 func: name.clone(),
                 args: arg_names,
                 result: None,
             });
-            
+
             Ok(Some(statements))
         }
         Statement::Return(expr) => {
@@ -397,9 +605,11 @@ fn convert_statement(
             Ok(Some(vec![IrStatement::Return { value }]))
         }
         Statement::EnterScope => {
+            *depth += 1;
             Ok(Some(vec![IrStatement::EnterScope]))
         }
         Statement::ExitScope => {
+            *depth = depth.saturating_sub(1);
             Ok(Some(vec![IrStatement::ExitScope]))
         }
         Statement::EnterLoop => {
@@ -420,6 +630,7 @@ mod tests {
     fn create_test_function(name: &str) -> Function {
         Function {
             name: name.to_string(),
+            qualified_name: name.to_string(),
             parameters: vec![],
             return_type: "void".to_string(),
             body: vec![],
@@ -440,6 +651,7 @@ mod tests {
             is_const: false,
             is_unique_ptr,
             is_shared_ptr: false,
+            is_union: false,
             location: SourceLocation {
                 file: "test.cpp".to_string(),
                 line: 1,
@@ -448,6 +660,13 @@ mod tests {
         }
     }
 
+    fn create_test_union_variable(name: &str, type_name: &str) -> Variable {
+        Variable {
+            is_union: true,
+            ..create_test_variable(name, type_name, false)
+        }
+    }
+
     #[test]
     fn test_build_empty_ir() {
         let ast = crate::parser::CppAst::new();
@@ -487,6 +706,37 @@ mod tests {
         assert!(matches!(var_info.ty, VariableType::UniquePtr(_)));
     }
 
+    #[test]
+    fn test_union_parameter_classifies_as_union_type() {
+        let union_var = create_test_union_variable("u", "MyUnion");
+        let mut ast = crate::parser::CppAst::new();
+        let mut func = create_test_function("test");
+        func.parameters.push(union_var);
+        ast.functions.push(func);
+
+        let result = build_ir(ast);
+        assert!(result.is_ok());
+
+        let ir = result.unwrap();
+        let var_info = ir.functions[0].variables.get("u").unwrap();
+        assert!(matches!(&var_info.ty, VariableType::Union(name) if name == "MyUnion"));
+    }
+
+    #[test]
+    fn test_union_local_declaration_classifies_as_union_type() {
+        let mut ast = crate::parser::CppAst::new();
+        let mut func = create_test_function("test");
+        func.body.push(crate::parser::Statement::VariableDecl(create_test_union_variable("u", "MyUnion")));
+        ast.functions.push(func);
+
+        let result = build_ir(ast);
+        assert!(result.is_ok());
+
+        let ir = result.unwrap();
+        let var_info = ir.functions[0].variables.get("u").unwrap();
+        assert!(matches!(&var_info.ty, VariableType::Union(name) if name == "MyUnion"));
+    }
+
     #[test]
     fn test_ownership_state_initialization() {
         let var = create_test_variable("x", "int", false);
@@ -510,9 +760,179 @@ mod tests {
             scope_start: 0,
             scope_end: 10,
         };
-        
+
         assert_eq!(lifetime.name, "a");
         assert_eq!(lifetime.scope_start, 0);
         assert_eq!(lifetime.scope_end, 10);
     }
+
+    #[test]
+    fn test_place_of_renders_field_projection_as_dotted_path() {
+        let place = crate::parser::Expression::Field {
+            base: Box::new(crate::parser::Expression::Variable("s".to_string())),
+            member: "a".to_string(),
+        };
+        assert_eq!(place_of(&place), Some("s.a".to_string()));
+    }
+
+    #[test]
+    fn test_place_of_has_no_place_for_a_call_result() {
+        let call = crate::parser::Expression::FunctionCall { name: "f".to_string(), args: vec![] };
+        assert_eq!(place_of(&call), None);
+    }
+
+    #[test]
+    fn test_reference_binding_to_field_borrows_the_field_not_the_whole_object() {
+        let mut variables = HashMap::new();
+        variables.insert(
+            "r".to_string(),
+            VariableInfo {
+                name: "r".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Uninitialized,
+                lifetime: None,
+            },
+        );
+        let mut depth = 0;
+        let stmt = crate::parser::Statement::ReferenceBinding {
+            name: "r".to_string(),
+            target: crate::parser::Expression::Field {
+                base: Box::new(crate::parser::Expression::Variable("s".to_string())),
+                member: "a".to_string(),
+            },
+            is_mutable: false,
+            location: SourceLocation { file: "test.cpp".to_string(), line: 1, column: 1 },
+        };
+
+        let signatures = SignatureTable::default();
+        let mut call_site = 0;
+        let result = convert_statement(&stmt, &mut variables, &mut depth, &signatures, &mut call_site).unwrap().unwrap();
+        assert_eq!(result.len(), 1);
+        match &result[0] {
+            IrStatement::Borrow { from, to, kind } => {
+                assert_eq!(from, "s.a");
+                assert_eq!(to, "r");
+                assert_eq!(*kind, BorrowKind::Immutable);
+            }
+            other => panic!("expected a Borrow statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_std_move_of_a_field_lowers_to_a_dotted_path_move() {
+        // `T t = std::move(s.a);` must move the field `s.a`, not silently
+        // drop the move (which would leave `s.a` looking un-moved and
+        // `s.other` wrongly treated as aliasing it).
+        let mut variables = HashMap::new();
+        variables.insert(
+            "t".to_string(),
+            VariableInfo {
+                name: "t".to_string(),
+                ty: VariableType::Owned("int".to_string()),
+                ownership: OwnershipState::Uninitialized,
+                lifetime: None,
+            },
+        );
+        let mut depth = 0;
+        let stmt = crate::parser::Statement::Assignment {
+            lhs: "t".to_string(),
+            rhs: crate::parser::Expression::Move(Box::new(crate::parser::Expression::Field {
+                base: Box::new(crate::parser::Expression::Variable("s".to_string())),
+                member: "a".to_string(),
+            })),
+            location: SourceLocation { file: "test.cpp".to_string(), line: 1, column: 1 },
+        };
+
+        let signatures = SignatureTable::default();
+        let mut call_site = 0;
+        let result = convert_statement(&stmt, &mut variables, &mut depth, &signatures, &mut call_site).unwrap().unwrap();
+        assert_eq!(result.len(), 1);
+        match &result[0] {
+            IrStatement::Move { from, to } => {
+                assert_eq!(from, "s.a");
+                assert_eq!(to, "t");
+            }
+            other => panic!("expected a Move statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_std_move_of_a_field_as_a_call_argument_lowers_to_a_dotted_path_move() {
+        let args = vec![crate::parser::Expression::Move(Box::new(crate::parser::Expression::Field {
+            base: Box::new(crate::parser::Expression::Variable("s".to_string())),
+            member: "a".to_string(),
+        }))];
+        let signatures = SignatureTable::default();
+        let mut call_site = 0;
+        let (statements, arg_names) = lower_call_args("consume", &args, &signatures, &mut call_site);
+
+        assert_eq!(arg_names, vec!["s.a".to_string()]);
+        assert_eq!(statements.len(), 1);
+        match &statements[0] {
+            IrStatement::Move { from, to } => {
+                assert_eq!(from, "s.a");
+                assert_eq!(to, "_temp_move_s.a");
+            }
+            other => panic!("expected a Move statement, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_repeated_calls_mutably_borrowing_the_same_variable_do_not_conflict() {
+        // void mutate(int& r);
+        // @safe
+        // void f() { int x = 0; mutate(x); mutate(x); }
+        //
+        // Each call's synthetic borrower name must be unique to that call
+        // site, or the second loan collides with the first's and the
+        // checker reports a false "already borrowed" conflict.
+        let loc = || SourceLocation { file: "test.cpp".to_string(), line: 1, column: 1 };
+
+        let mutate = Function {
+            name: "mutate".to_string(),
+            qualified_name: "mutate".to_string(),
+            parameters: vec![Variable {
+                name: "r".to_string(),
+                type_name: "int".to_string(),
+                is_reference: true,
+                is_pointer: false,
+                is_const: false,
+                is_unique_ptr: false,
+                is_shared_ptr: false,
+                is_union: false,
+                location: loc(),
+            }],
+            return_type: "void".to_string(),
+            body: vec![],
+            location: loc(),
+        };
+
+        let call_mutate_x = || crate::parser::Statement::FunctionCall {
+            name: "mutate".to_string(),
+            args: vec![crate::parser::Expression::Variable("x".to_string())],
+            location: loc(),
+        };
+
+        let f = Function {
+            name: "f".to_string(),
+            qualified_name: "f".to_string(),
+            parameters: vec![],
+            return_type: "void".to_string(),
+            body: vec![
+                crate::parser::Statement::VariableDecl(create_test_variable("x", "int", false)),
+                call_mutate_x(),
+                call_mutate_x(),
+            ],
+            location: loc(),
+        };
+
+        let ast = CppAst { functions: vec![mutate, f], global_variables: vec![] };
+        let program = build_ir(ast).expect("IR lowering should succeed");
+        let errors = crate::analysis::check_borrows(program).expect("borrow check should run");
+        assert!(
+            errors.is_empty(),
+            "two calls each mutably borrowing 'x' for their own duration should not conflict: {:?}",
+            errors
+        );
+    }
 }
\ No newline at end of file
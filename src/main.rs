@@ -1,14 +1,12 @@
 use clap::Parser;
 use colored::*;
 use std::path::PathBuf;
-use std::fs;
 use std::env;
-use serde_json;
+use serde_json::json;
 
 mod parser;
 mod ir;
 mod analysis;
-mod solver;
 mod diagnostics;
 
 #[derive(clap::Parser, Debug)]
@@ -38,60 +36,327 @@ struct Args {
     #[arg(short, long, action = clap::ArgAction::Count)]
     verbose: u8,
 
-    /// Output format (text, json)
-    #[arg(long, default_value = "text")]
-    format: String,
+    /// Output format. `--error-format` is accepted as an alias, matching
+    /// rustc's flag name. `json` prints one document holding a
+    /// `diagnostics` array (one object per diagnostic) plus a summary
+    /// count, rather than text meant for a human to read.
+    #[arg(long, visible_alias = "error-format", value_enum, default_value_t = OutputFormat::Text)]
+    format: OutputFormat,
+
+    /// Rank functions by unsafe surface area (pointer derefs, unsafe
+    /// casts, unsafe calls) instead of running the borrow-check passes
+    #[arg(long)]
+    audit: bool,
+
+    /// Include test files in the `--audit` report (skipped by default)
+    #[arg(long = "include-tests")]
+    include_tests: bool,
+
+    /// Default safety policy to use when a file has no `@safe-policy`
+    /// pragma of its own (which always takes precedence over this flag)
+    #[arg(long, value_enum)]
+    safety: Option<SafetyPolicyArg>,
+
+    /// Project config file (`rustycpp.toml`) supplying the `[allowlist]`
+    /// call lists for the `allowlisted-safe` policy, and the `[headers]`
+    /// allow/block lists scoping which headers get parsed for signatures
+    #[arg(long, value_name = "FILE")]
+    allowlist_file: Option<PathBuf>,
+
+    /// Number of worker threads to run the per-function checks on, analogous
+    /// to `rustc -C codegen-units`. Defaults to the available parallelism.
+    #[arg(long, value_name = "N")]
+    jobs: Option<usize>,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+enum OutputFormat {
+    /// Colored, rustc-style text with source snippets
+    Text,
+    /// One JSON object per violation plus a summary, for editors/CI
+    Json,
+}
+
+/// CLI-facing mirror of [`parser::safety_annotations::SafetyPolicy`] --
+/// `clap::ValueEnum` needs to live on a type in this crate, so this is a
+/// thin shell that converts into the real enum rather than deriving it
+/// directly onto `SafetyPolicy`.
+#[derive(clap::ValueEnum, Clone, Debug, PartialEq)]
+enum SafetyPolicyArg {
+    /// Every function is checked-safe unless explicitly marked `@unsafe`
+    AllSafe,
+    /// Every function is unchecked unless explicitly marked `@safe` (the historical default)
+    AllUnsafe,
+    /// Every function is checked-safe by default, but raw pointer/reference
+    /// parameters must carry an explicit lifetime annotation
+    ReferencesWrapped,
+    /// Every function is checked-safe by default; whether a called function
+    /// is itself safe is decided by `--allowlist-file`'s `[allowlist]`
+    /// table instead of this crate's own built-in guess
+    AllowlistedSafe,
+}
+
+impl From<SafetyPolicyArg> for parser::safety_annotations::SafetyPolicy {
+    fn from(arg: SafetyPolicyArg) -> Self {
+        match arg {
+            SafetyPolicyArg::AllSafe => parser::safety_annotations::SafetyPolicy::AllFunctionsSafe,
+            SafetyPolicyArg::AllUnsafe => parser::safety_annotations::SafetyPolicy::AllFunctionsUnsafe,
+            SafetyPolicyArg::ReferencesWrapped => parser::safety_annotations::SafetyPolicy::ReferencesWrapped,
+            SafetyPolicyArg::AllowlistedSafe => parser::safety_annotations::SafetyPolicy::AllowlistedSafe,
+        }
+    }
 }
 
 fn main() {
     let args = Args::parse();
-    
-    println!("{}", "Rusty C++ Checker".bold().blue());
-    println!("Analyzing: {}", args.input.display());
-    
-    match analyze_file(&args.input, &args.include_paths, args.compile_commands.as_ref()) {
-        Ok(results) => {
-            if results.is_empty() {
-                println!("{}", "✓ No borrow checking violations found!".green());
-            } else {
-                println!("{}", format!("✗ Found {} violation(s):", results.len()).red());
-                for error in results {
-                    println!("{}", error);
+
+    if args.format == OutputFormat::Text {
+        println!("{}", "Rusty C++ Checker".bold().blue());
+        println!("Analyzing: {}", args.input.display());
+    }
+
+    if args.audit {
+        match run_audit(&args.input, &args.include_paths, args.compile_commands.as_ref(), args.include_tests) {
+            Ok(entries) => match args.format {
+                OutputFormat::Text => report_audit_text(&entries),
+                OutputFormat::Json => report_audit_json(&entries),
+            },
+            Err(e) => match args.format {
+                OutputFormat::Json => {
+                    println!("{}", json!({"level": "error", "message": e}));
+                    std::process::exit(1);
+                }
+                OutputFormat::Text => {
+                    eprintln!("{}: {}", "Error".red().bold(), e);
+                    std::process::exit(1);
                 }
+            },
+        }
+        return;
+    }
+
+    match analyze_file(
+        &args.input,
+        &args.include_paths,
+        args.compile_commands.as_ref(),
+        args.safety.clone().map(Into::into),
+        args.allowlist_file.as_ref(),
+        args.jobs,
+    ) {
+        Ok(results) => match args.format {
+            OutputFormat::Text => report_text(&results),
+            OutputFormat::Json => report_json(&results),
+        },
+        Err(e) => match args.format {
+            OutputFormat::Json => {
+                println!("{}", json!({"level": "error", "message": e}));
                 std::process::exit(1);
             }
-        }
-        Err(e) => {
-            eprintln!("{}: {}", "Error".red().bold(), e);
-            std::process::exit(1);
+            OutputFormat::Text => {
+                eprintln!("{}: {}", "Error".red().bold(), e);
+                std::process::exit(1);
+            }
+        },
+    }
+}
+
+fn report_text(results: &[diagnostics::BorrowCheckDiagnostic]) {
+    if results.is_empty() {
+        println!("{}", "✓ No borrow checking violations found!".green());
+        return;
+    }
+
+    println!("{}", format!("✗ Found {} violation(s):", results.len()).red());
+    let mut source_map = diagnostics::SourceMap::new();
+    for diagnostic in results {
+        println!("{}", source_map.render(diagnostic));
+    }
+    std::process::exit(1);
+}
+
+fn report_json(results: &[diagnostics::BorrowCheckDiagnostic]) {
+    let summary = json!({
+        "diagnostics": results.iter().map(|d| d.to_json()).collect::<Vec<_>>(),
+        "summary": {
+            "violation_count": results.len(),
+            "status": if results.is_empty() { "ok" } else { "error" },
+        },
+    });
+    println!("{}", serde_json::to_string_pretty(&summary).expect("diagnostics are always valid JSON"));
+    if !results.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn report_audit_text(entries: &[analysis::unsafe_audit::UnsafeAuditEntry]) {
+    if entries.is_empty() {
+        println!("{}", "✓ No functions to audit.".green());
+        return;
+    }
+
+    println!(
+        "{}",
+        format!("Unsafe surface area for {} function(s), most unsafe first:", entries.len()).bold()
+    );
+    for entry in entries {
+        println!(
+            "  {:>3}  {}  (derefs: {}, casts: {}, unsafe calls: {}, transitive: {})",
+            entry.score(),
+            entry.qualified_name.cyan(),
+            entry.pointer_derefs,
+            entry.unsafe_casts,
+            entry.direct_unsafe_calls,
+            entry.transitive_unsafe_calls,
+        );
+    }
+}
+
+fn report_audit_json(entries: &[analysis::unsafe_audit::UnsafeAuditEntry]) {
+    let report = json!({
+        "audit": entries.iter().map(|entry| json!({
+            "function": entry.qualified_name,
+            "file": entry.file,
+            "pointer_derefs": entry.pointer_derefs,
+            "unsafe_casts": entry.unsafe_casts,
+            "direct_unsafe_calls": entry.direct_unsafe_calls,
+            "transitive_unsafe_calls": entry.transitive_unsafe_calls,
+            "score": entry.score(),
+        })).collect::<Vec<_>>(),
+        "summary": { "function_count": entries.len() },
+    });
+    println!("{}", serde_json::to_string_pretty(&report).expect("audit report is always valid JSON"));
+}
+
+/// Build the diagnostic for a function `find_unannotated_reference_functions`
+/// flagged under the `references-wrapped` policy. No clang `Entity` survives
+/// past the header-signature lookup that found it, so -- like the borrow
+/// checker's own violations below -- this has no precise span to point at.
+fn unannotated_reference_function_diagnostic(function_name: String) -> diagnostics::BorrowCheckDiagnostic {
+    diagnostics::BorrowCheckDiagnostic {
+        severity: diagnostics::Severity::Error,
+        message: format!(
+            "function '{}' takes a raw pointer/reference parameter with no `@lifetime` annotation",
+            function_name
+        ),
+        location: diagnostics::Location { file: String::new(), line: 0, column: 0, span: None },
+        help: Some("add an `@lifetime` annotation to the parameter, or mark the function `@unsafe`".to_string()),
+        notes: vec![],
+        labels: vec![],
+        function: Some(function_name),
+        suggestion: None,
+        code: Some("RUSTYCPP-E0005"),
+    }
+}
+
+fn run_audit(
+    path: &PathBuf,
+    include_paths: &[PathBuf],
+    compile_commands: Option<&PathBuf>,
+    include_tests: bool,
+) -> Result<Vec<analysis::unsafe_audit::UnsafeAuditEntry>, String> {
+    let mut all_include_paths = include_paths.to_vec();
+    all_include_paths.extend(extract_include_paths_from_env());
+
+    let mut defines = Vec::new();
+    let mut std_override = None;
+    if let Some(cc_path) = compile_commands {
+        let flags = parser::compile_commands::extract_compile_flags(cc_path, path)?;
+        all_include_paths.extend(flags.include_paths);
+        defines = flags.defines;
+        std_override = flags.std;
+    }
+
+    let ast = parser::parse_cpp_file_with_config(path, &all_include_paths, &defines, std_override.as_deref())?;
+    let safety_context = parser::safety_annotations::parse_safety_annotations(path)?;
+
+    let mut known_safe_functions = std::collections::HashSet::new();
+    for (func_name, mode) in &safety_context.function_overrides {
+        if *mode == parser::safety_annotations::SafetyMode::Safe {
+            known_safe_functions.insert(func_name.clone());
         }
     }
+
+    Ok(analysis::unsafe_audit::audit_unsafe_usage(
+        &ast,
+        &safety_context,
+        &known_safe_functions,
+        include_tests,
+    ))
 }
 
-fn analyze_file(path: &PathBuf, include_paths: &[PathBuf], compile_commands: Option<&PathBuf>) -> Result<Vec<String>, String> {
+fn analyze_file(
+    path: &PathBuf,
+    include_paths: &[PathBuf],
+    compile_commands: Option<&PathBuf>,
+    default_policy: Option<parser::safety_annotations::SafetyPolicy>,
+    allowlist_file: Option<&PathBuf>,
+    jobs: Option<usize>,
+) -> Result<Vec<diagnostics::BorrowCheckDiagnostic>, String> {
     // Start with CLI-provided include paths
     let mut all_include_paths = include_paths.to_vec();
-    
+
     // Add include paths from environment variables
     all_include_paths.extend(extract_include_paths_from_env());
-    
-    // Extract include paths from compile_commands.json if provided
+
+    // Extract include paths, defines, and a `-std=` override from
+    // compile_commands.json if provided, so the file parses under the
+    // same configuration the real build uses.
+    let mut defines = Vec::new();
+    let mut std_override = None;
     if let Some(cc_path) = compile_commands {
-        let extracted_paths = extract_include_paths_from_compile_commands(cc_path, path)?;
-        all_include_paths.extend(extracted_paths);
+        let flags = parser::compile_commands::extract_compile_flags(cc_path, path)?;
+        all_include_paths.extend(flags.include_paths);
+        defines = flags.defines;
+        std_override = flags.std;
     }
-    
-    // Parse included headers for lifetime annotations
+
+    // Read the config file (if any) once up front, so a missing/malformed
+    // file is reported the same way any other config error is, and both
+    // the `[headers]` and `[allowlist]` tables below come from one read.
+    let config_contents = match allowlist_file {
+        Some(allowlist_path) => Some(
+            std::fs::read_to_string(allowlist_path)
+                .map_err(|e| format!("Failed to read allowlist file {}: {}", allowlist_path.display(), e))?,
+        ),
+        None => None,
+    };
+
+    // Parse included headers for lifetime annotations. The `[headers]`
+    // table of the config file lets a project scope header parsing to
+    // just the headers/names it cares about, the same way `[allowlist]`
+    // scopes which calls count as safe -- apply it before parsing so it
+    // actually restricts which headers get opened.
     let mut header_cache = parser::HeaderCache::new();
     header_cache.set_include_paths(all_include_paths.clone());
+    if let Some(contents) = &config_contents {
+        let headers_config = parser::header_cache::HeaderAllowBlockConfig::from_toml(contents)?;
+        header_cache.set_allow_list(headers_config.allow_names, headers_config.allow_header_globs);
+        header_cache.set_block_list(headers_config.block_names, headers_config.block_header_globs);
+    }
     header_cache.parse_includes_from_source(path)?;
-    
+
     // Parse the C++ file with include paths
-    let ast = parser::parse_cpp_file_with_includes(path, &all_include_paths)?;
-    
+    let ast = parser::parse_cpp_file_with_config(path, &all_include_paths, &defines, std_override.as_deref())?;
+
     // Parse safety annotations using the unified rule
-    let safety_context = parser::safety_annotations::parse_safety_annotations(path)?;
-    
+    let mut safety_context = parser::safety_annotations::parse_safety_annotations(path)?;
+
+    // A file's own `// @safe-policy: ...` pragma always wins; the
+    // `--safety` flag only supplies a default for files that don't set one,
+    // so a migration can flip the whole build to safe-by-default without
+    // having to touch every file that already opted into its own policy.
+    if safety_context.policy.is_none() {
+        safety_context.policy = default_policy;
+    }
+
+    // Under `allowlisted-safe`, the project's own allowlist file decides
+    // which called functions count as safe instead of the crate's baked-in
+    // guess.
+    if let Some(contents) = &config_contents {
+        let allowlist = parser::safety_annotations::CallAllowlist::from_toml(contents)?;
+        safety_context.set_allowlist(allowlist);
+    }
+
     // Build a set of known safe functions from the safety context
     let mut known_safe_functions = std::collections::HashSet::new();
     for (func_name, mode) in &safety_context.function_overrides {
@@ -100,78 +365,120 @@ fn analyze_file(path: &PathBuf, include_paths: &[PathBuf], compile_commands: Opt
         }
     }
     
-    // Check for unsafe pointer operations and unsafe propagation in safe functions
-    let mut violations = Vec::new();
-    for function in &ast.functions {
-        if safety_context.should_check_function(&function.name) {
-            // Check for pointer operations
-            let pointer_errors = analysis::pointer_safety::check_parsed_function_for_pointers(function);
-            violations.extend(pointer_errors);
-            
-            // Check for calls to unsafe functions
-            let propagation_errors = analysis::unsafe_propagation::check_unsafe_propagation(
-                function,
-                &safety_context,
-                &known_safe_functions
-            );
-            violations.extend(propagation_errors);
-        }
-    }
-    
+    // Check for unsafe pointer operations and unsafe propagation in safe
+    // functions. Each function is checked independently of every other --
+    // only `safety_context`/`known_safe_functions` are shared, and both are
+    // read-only snapshots by this point -- so this fans out across a worker
+    // pool instead of walking `ast.functions` serially.
+    let mut violations = run_per_function_checks(&ast.functions, &safety_context, &known_safe_functions, jobs);
+
+    // Check that functions returning a reference carry a consistent
+    // `@lifetime` annotation, before `ast`/`header_cache` are moved below.
+    violations.extend(analysis::lifetime_checker::check_return_lifetime_annotations(
+        &ast,
+        &header_cache,
+    ));
+
+    // Under `--safety references-wrapped`, flag rather than silently skip
+    // a function whose raw pointer/reference parameter has no explicit
+    // lifetime annotation (a no-op under every other policy).
+    let function_names: Vec<String> = ast.functions.iter().map(|f| f.name.clone()).collect();
+    let unannotated = parser::safety_annotations::find_unannotated_reference_functions(
+        &safety_context,
+        &header_cache,
+        &function_names,
+    );
+    violations.extend(unannotated.into_iter().map(unannotated_reference_function_diagnostic));
+
     // Build intermediate representation with safety context
     let ir = ir::build_ir_with_safety_context(ast, safety_context.clone())?;
-    
+
     // Perform borrow checking analysis with header knowledge and safety context
+    let path_str = path.display().to_string();
     let borrow_violations = analysis::check_borrows_with_safety_context(ir, header_cache, safety_context)?;
-    violations.extend(borrow_violations);
-    
+    violations.extend(borrow_violations.into_iter().map(|message| diagnostics::BorrowCheckDiagnostic {
+        severity: diagnostics::Severity::Error,
+        message,
+        location: diagnostics::Location {
+            file: path_str.clone(),
+            line: 0,
+            column: 0,
+            span: None,
+        },
+        help: None,
+        notes: vec![],
+        labels: vec![],
+        function: None,
+        suggestion: None,
+        code: None,
+    }));
+
     Ok(violations)
 }
 
-fn extract_include_paths_from_compile_commands(cc_path: &PathBuf, source_file: &PathBuf) -> Result<Vec<PathBuf>, String> {
-    let content = fs::read_to_string(cc_path)
-        .map_err(|e| format!("Failed to read compile_commands.json: {}", e))?;
-    
-    let commands: Vec<serde_json::Value> = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse compile_commands.json: {}", e))?;
-    
-    let source_str = source_file.to_string_lossy();
-    
-    // Find the entry for our source file
-    for entry in commands {
-        if let Some(file) = entry.get("file").and_then(|f| f.as_str()) {
-            if file.ends_with(&*source_str) || source_str.ends_with(file) {
-                if let Some(command) = entry.get("command").and_then(|c| c.as_str()) {
-                    return extract_include_paths_from_command(command);
-                }
-            }
-        }
-    }
-    
-    Ok(Vec::new()) // No matching entry found
-}
+/// Runs the per-function checks (pointer safety, unsafe propagation,
+/// unnecessary-unsafe) over `functions` on a pool of `jobs` worker threads
+/// (defaulting to [`std::thread::available_parallelism`]), then reassembles
+/// the results in source order. `safety_context` and `known_safe_functions`
+/// are built once up front and only ever read here, so workers need no
+/// locking to share them.
+///
+/// Diagnostic order is independent of thread scheduling: each worker tags
+/// its output with the original function index before handing it back, and
+/// the indices are sorted before the per-function diagnostic vectors are
+/// flattened.
+fn run_per_function_checks(
+    functions: &[parser::Function],
+    safety_context: &parser::safety_annotations::SafetyContext,
+    known_safe_functions: &std::collections::HashSet<String>,
+    jobs: Option<usize>,
+) -> Vec<diagnostics::BorrowCheckDiagnostic> {
+    let checked_indices: Vec<usize> = functions
+        .iter()
+        .enumerate()
+        .filter(|(_, function)| safety_context.should_check_function(&function.name))
+        .map(|(index, _)| index)
+        .collect();
 
-fn extract_include_paths_from_command(command: &str) -> Result<Vec<PathBuf>, String> {
-    let mut paths = Vec::new();
-    let parts: Vec<&str> = command.split_whitespace().collect();
-    
-    let mut i = 0;
-    while i < parts.len() {
-        if parts[i] == "-I" && i + 1 < parts.len() {
-            // -I /path/to/include
-            paths.push(PathBuf::from(parts[i + 1]));
-            i += 2;
-        } else if parts[i].starts_with("-I") {
-            // -I/path/to/include
-            let path = &parts[i][2..];
-            paths.push(PathBuf::from(path));
-            i += 1;
-        } else {
-            i += 1;
-        }
-    }
-    
-    Ok(paths)
+    let worker_count = jobs
+        .unwrap_or_else(|| std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1))
+        .max(1)
+        .min(checked_indices.len().max(1));
+
+    let chunk_size = (checked_indices.len() + worker_count - 1) / worker_count.max(1);
+    let chunk_size = chunk_size.max(1);
+
+    let mut per_function: Vec<(usize, Vec<diagnostics::BorrowCheckDiagnostic>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = checked_indices
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&index| {
+                            let function = &functions[index];
+                            let mut diagnostics = analysis::pointer_safety::check_parsed_function_for_pointers(function);
+                            diagnostics.extend(analysis::unsafe_propagation::check_unsafe_propagation(
+                                function,
+                                safety_context,
+                                known_safe_functions,
+                            ));
+                            diagnostics.extend(analysis::unnecessary_unsafe::check_unnecessary_unsafe_blocks(
+                                function,
+                                safety_context,
+                                known_safe_functions,
+                            ));
+                            (index, diagnostics)
+                        })
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        handles.into_iter().flat_map(|handle| handle.join().expect("per-function check worker panicked")).collect()
+    });
+
+    per_function.sort_by_key(|(index, _)| *index);
+    per_function.into_iter().flat_map(|(_, diagnostics)| diagnostics).collect()
 }
 
 fn extract_include_paths_from_env() -> Vec<PathBuf> {
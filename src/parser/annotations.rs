@@ -1,14 +1,75 @@
 use clang::Entity;
 use regex::Regex;
+use std::fmt;
+
+/// A named lifetime from the `'a`/`'b`/... positional convention, upheld
+/// to never be `static` or `_` -- those are [`Lifetime::Static`] and
+/// [`Lifetime::Anonymous`] instead, so a named lifetime can never
+/// accidentally alias either.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct NamedLifetime(String);
+
+impl NamedLifetime {
+    /// Returns `None` for `"static"` or `"_"` -- callers that see either of
+    /// those should construct [`Lifetime::Static`] or
+    /// [`Lifetime::Anonymous`] directly instead.
+    pub fn new(name: impl Into<String>) -> Option<Self> {
+        let name = name.into();
+        if name == "static" || name == "_" {
+            None
+        } else {
+            Some(Self(name))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// A lifetime/region as it appears in an `@lifetime` annotation or is
+/// assigned during elision: either the `'static` region that outlives
+/// everything, an anonymous region elision has given a stable index
+/// (instead of a synthesized string like `'arg0` that could collide with
+/// an actually-named `'arg0`), or a named region.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Lifetime {
+    Static,
+    Anonymous(usize),
+    Named(NamedLifetime),
+}
+
+impl Lifetime {
+    /// Build a [`Lifetime`] from a parsed name, recognizing `'static`
+    /// (`"_"` never reaches here -- elision always assigns a name, and the
+    /// annotation grammar has no syntax for an elided `'_`).
+    pub fn named(name: impl Into<String>) -> Self {
+        let name = name.into();
+        match NamedLifetime::new(name) {
+            Some(named) => Lifetime::Named(named),
+            None => Lifetime::Static,
+        }
+    }
+}
+
+impl fmt::Display for Lifetime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Lifetime::Static => write!(f, "'static"),
+            Lifetime::Anonymous(index) => write!(f, "'_{}", index),
+            Lifetime::Named(name) => write!(f, "'{}", name.as_str()),
+        }
+    }
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum LifetimeAnnotation {
     // 'a, 'b, etc - just the lifetime name
-    Lifetime(String),
+    Lifetime(Lifetime),
     // &'a T - immutable reference with lifetime
-    Ref(String),
-    // &'a mut T - mutable reference with lifetime  
-    MutRef(String),
+    Ref(Lifetime),
+    // &'a mut T - mutable reference with lifetime
+    MutRef(Lifetime),
     // owned - for ownership transfer
     Owned,
 }
@@ -28,10 +89,77 @@ pub struct FunctionSignature {
     pub safety: Option<SafetyAnnotation>, // @safe or @unsafe
 }
 
+impl FunctionSignature {
+    /// This signature's [`LifetimeEnv`], built fresh each time rather than
+    /// cached on the struct -- a real signature only ever names a handful
+    /// of lifetimes, so there's no need to keep every construction site
+    /// (parsing, elision, tests) in sync with a derived field.
+    pub fn lifetime_env(&self) -> LifetimeEnv {
+        LifetimeEnv::from_signature(self)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct LifetimeBound {
-    pub longer: String,  // 'a in 'a: 'b
-    pub shorter: String, // 'b in 'a: 'b
+    pub longer: Lifetime,  // 'a in 'a: 'b
+    pub shorter: Lifetime, // 'b in 'a: 'b
+}
+
+/// Every named lifetime a [`FunctionSignature`] declares, in the
+/// first-occurrence order they appear across `param_lifetimes`, each paired
+/// with the index of the parameter it first appears on -- the index a call
+/// site's actual argument lifetime is substituted from. Replaces the old
+/// hardcoded `'a`/`'b`/`'c` convention `map_lifetime_to_actual` used to
+/// rely on, so a signature can declare any number of lifetimes under any
+/// names. Stored as a plain `Vec`, since a real C++ signature only ever
+/// declares a handful.
+#[derive(Debug, Clone, Default)]
+pub struct LifetimeEnv {
+    names: Vec<(NamedLifetime, usize)>,
+}
+
+impl LifetimeEnv {
+    pub fn from_signature(sig: &FunctionSignature) -> Self {
+        let mut names: Vec<(NamedLifetime, usize)> = Vec::new();
+        for (index, param) in sig.param_lifetimes.iter().enumerate() {
+            let lifetime = match param {
+                Some(LifetimeAnnotation::Ref(l) | LifetimeAnnotation::MutRef(l) | LifetimeAnnotation::Lifetime(l)) => Some(l),
+                Some(LifetimeAnnotation::Owned) | None => None,
+            };
+            if let Some(Lifetime::Named(name)) = lifetime {
+                if !names.iter().any(|(n, _)| n == name) {
+                    names.push((name.clone(), index));
+                }
+            }
+        }
+        Self { names }
+    }
+
+    /// The parameter index `name` first appears at, if this signature
+    /// declares it at all.
+    pub fn position(&self, name: &NamedLifetime) -> Option<usize> {
+        self.names.iter().find(|(n, _)| n == name).map(|(_, index)| *index)
+    }
+
+    /// Resolve a lifetime as it appears in the signature (`'static`, an
+    /// elision-assigned `Anonymous` index, or a name declared somewhere in
+    /// `param_lifetimes`) to the actual lifetime bound to the
+    /// corresponding argument at a call site. `None` if `lifetime` names a
+    /// parameter position the call doesn't have an actual lifetime for
+    /// (an owned argument, or a name this signature never declared).
+    ///
+    /// Note: a method receiver's elided lifetime (rule 3 in
+    /// `lifetime_elision`) is resolved positionally here too, same as any
+    /// other parameter -- `param_lifetimes`/`arg_lifetimes` have no separate
+    /// "this" slot, so callers must already include the receiver as the
+    /// trailing entry in `arg_lifetimes` for that substitution to line up.
+    pub fn resolve(&self, lifetime: &Lifetime, arg_lifetimes: &[Option<Lifetime>]) -> Option<Lifetime> {
+        match lifetime {
+            Lifetime::Static => Some(Lifetime::Static),
+            Lifetime::Anonymous(index) => arg_lifetimes.get(*index).and_then(|l| l.clone()),
+            Lifetime::Named(name) => self.position(name).and_then(|i| arg_lifetimes.get(i)).and_then(|l| l.clone()),
+        }
+    }
 }
 
 pub fn extract_annotations(entity: &Entity) -> Option<FunctionSignature> {
@@ -139,47 +267,50 @@ fn parse_param_lifetimes(params_str: &str) -> Vec<Option<LifetimeAnnotation>> {
 
 fn parse_single_lifetime(lifetime_str: &str) -> Option<LifetimeAnnotation> {
     let trimmed = lifetime_str.trim();
-    
+
     if trimmed == "owned" {
         Some(LifetimeAnnotation::Owned)
     } else if trimmed.starts_with("&'") && trimmed.contains("mut") {
         // &'a mut T
-        let lifetime_name = extract_lifetime_name(trimmed);
-        lifetime_name.map(|name| LifetimeAnnotation::MutRef(name))
+        let lifetime = extract_lifetime_name(trimmed);
+        lifetime.map(LifetimeAnnotation::MutRef)
     } else if trimmed.starts_with("&'") {
         // &'a T
-        let lifetime_name = extract_lifetime_name(trimmed);
-        lifetime_name.map(|name| LifetimeAnnotation::Ref(name))
+        let lifetime = extract_lifetime_name(trimmed);
+        lifetime.map(LifetimeAnnotation::Ref)
     } else if trimmed.starts_with('\'') {
         // Just 'a
-        Some(LifetimeAnnotation::Lifetime(trimmed.to_string()))
+        extract_lifetime_name(trimmed).map(LifetimeAnnotation::Lifetime)
     } else {
         None
     }
 }
 
-fn extract_lifetime_name(s: &str) -> Option<String> {
+/// Extract the name out of a `'a`/`'static`/... fragment and classify it,
+/// so `'static` is recognized here rather than being carried around as an
+/// ordinary named lifetime that just happens to be spelled `"static"`.
+fn extract_lifetime_name(s: &str) -> Option<Lifetime> {
     let re = Regex::new(r"'([a-z][a-z0-9]*)").ok()?;
     re.captures(s)
         .and_then(|cap| cap.get(1))
-        .map(|m| m.as_str().to_string())
+        .map(|m| Lifetime::named(m.as_str()))
 }
 
 fn parse_lifetime_bounds(bounds_str: &str) -> Vec<LifetimeBound> {
     let mut bounds = Vec::new();
-    
+
     // Parse patterns like 'a: 'b
     let bound_re = Regex::new(r"'([a-z][a-z0-9]*)\s*:\s*'([a-z][a-z0-9]*)").unwrap();
-    
+
     for cap in bound_re.captures_iter(bounds_str) {
         if let (Some(longer), Some(shorter)) = (cap.get(1), cap.get(2)) {
             bounds.push(LifetimeBound {
-                longer: longer.as_str().to_string(),
-                shorter: shorter.as_str().to_string(),
+                longer: Lifetime::named(longer.as_str()),
+                shorter: Lifetime::named(shorter.as_str()),
             });
         }
     }
-    
+
     bounds
 }
 
@@ -193,7 +324,7 @@ mod tests {
         let sig = parse_lifetime_annotations(comment, "test".to_string()).unwrap();
         
         assert_eq!(sig.name, "test");
-        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::Ref("a".to_string())));
+        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::Ref(Lifetime::named("a"))));
         assert!(sig.param_lifetimes.is_empty());
     }
     
@@ -211,19 +342,82 @@ mod tests {
         let sig = parse_lifetime_annotations(comment, "test".to_string()).unwrap();
         
         assert_eq!(sig.param_lifetimes.len(), 2);
-        assert_eq!(sig.param_lifetimes[0], Some(LifetimeAnnotation::Ref("a".to_string())));
-        assert_eq!(sig.param_lifetimes[1], Some(LifetimeAnnotation::Ref("b".to_string())));
-        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::Ref("a".to_string())));
+        assert_eq!(sig.param_lifetimes[0], Some(LifetimeAnnotation::Ref(Lifetime::named("a"))));
+        assert_eq!(sig.param_lifetimes[1], Some(LifetimeAnnotation::Ref(Lifetime::named("b"))));
+        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::Ref(Lifetime::named("a"))));
         assert_eq!(sig.lifetime_bounds.len(), 1);
-        assert_eq!(sig.lifetime_bounds[0].longer, "a");
-        assert_eq!(sig.lifetime_bounds[0].shorter, "b");
+        assert_eq!(sig.lifetime_bounds[0].longer, Lifetime::named("a"));
+        assert_eq!(sig.lifetime_bounds[0].shorter, Lifetime::named("b"));
     }
-    
+
     #[test]
     fn test_parse_mut_ref() {
         let comment = "// @lifetime: &'a mut";
         let sig = parse_lifetime_annotations(comment, "test".to_string()).unwrap();
-        
-        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::MutRef("a".to_string())));
+
+        assert_eq!(sig.return_lifetime, Some(LifetimeAnnotation::MutRef(Lifetime::named("a"))));
+    }
+
+    #[test]
+    fn test_static_lifetime_is_not_a_named_lifetime() {
+        assert_eq!(NamedLifetime::new("static"), None);
+        assert_eq!(NamedLifetime::new("_"), None);
+        assert_eq!(Lifetime::named("static"), Lifetime::Static);
+    }
+
+    #[test]
+    fn test_lifetime_display_round_trips_through_annotation_syntax() {
+        assert_eq!(Lifetime::named("a").to_string(), "'a");
+        assert_eq!(Lifetime::Static.to_string(), "'static");
+        assert_eq!(Lifetime::Anonymous(2).to_string(), "'_2");
+    }
+
+    #[test]
+    fn test_lifetime_env_resolves_by_declared_position_not_hardcoded_letters() {
+        // Four parameters and non a/b/c names -- the old hardcoded
+        // `map_lifetime_to_actual` would have mis-mapped or dropped these.
+        let comment = "// @lifetime: (&'first, &'second, &'third, &'fourth) -> &'fourth";
+        let sig = parse_lifetime_annotations(comment, "pick4".to_string()).unwrap();
+        let env = sig.lifetime_env();
+
+        let arg_lifetimes = vec![
+            Some(Lifetime::named("x1")),
+            Some(Lifetime::named("x2")),
+            Some(Lifetime::named("x3")),
+            Some(Lifetime::named("x4")),
+        ];
+
+        let LifetimeAnnotation::Ref(return_lifetime) = sig.return_lifetime.as_ref().unwrap() else {
+            panic!("expected a Ref return lifetime");
+        };
+        assert_eq!(env.resolve(return_lifetime, &arg_lifetimes), Some(Lifetime::named("x4")));
+    }
+
+    #[test]
+    fn test_lifetime_env_shared_name_resolves_to_same_position_everywhere() {
+        let comment = "// @lifetime: (&'a, &'a) -> &'a";
+        let sig = parse_lifetime_annotations(comment, "pick_shared".to_string()).unwrap();
+        let env = sig.lifetime_env();
+
+        assert_eq!(env.position(&NamedLifetime::new("a").unwrap()), Some(0));
+
+        let arg_lifetimes = vec![Some(Lifetime::named("x1")), Some(Lifetime::named("x2"))];
+        let LifetimeAnnotation::Ref(return_lifetime) = sig.return_lifetime.as_ref().unwrap() else {
+            panic!("expected a Ref return lifetime");
+        };
+        // 'a's first occurrence is parameter 0, so that's what the return
+        // resolves through.
+        assert_eq!(env.resolve(return_lifetime, &arg_lifetimes), Some(Lifetime::named("x1")));
+    }
+
+    #[test]
+    fn test_lifetime_env_unresolved_name_and_owned_argument_are_none() {
+        let comment = "// @lifetime: (&'a, owned) -> &'a";
+        let sig = parse_lifetime_annotations(comment, "mixed".to_string()).unwrap();
+        let env = sig.lifetime_env();
+
+        assert_eq!(env.resolve(&Lifetime::named("unrelated"), &[Some(Lifetime::named("x1"))]), None);
+        assert_eq!(env.resolve(&Lifetime::named("a"), &[None]), None); // arg 0 is owned, no lifetime
+        assert_eq!(env.resolve(&Lifetime::Static, &[]), Some(Lifetime::Static));
     }
 }
\ No newline at end of file
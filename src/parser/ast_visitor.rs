@@ -18,6 +18,12 @@ impl CppAst {
 #[derive(Debug, Clone)]
 pub struct Function {
     pub name: String,
+    /// Fully-qualified name (e.g. `myapp::Widget::run`), same convention as
+    /// `safety_annotations::qualified_name`, so callers that need to
+    /// distinguish overloads or same-named methods in different
+    /// namespaces/classes (like the unsafe-usage audit) don't have to
+    /// re-derive it from the entity themselves.
+    pub qualified_name: String,
     pub parameters: Vec<Variable>,
     #[allow(dead_code)]
     pub return_type: String,
@@ -38,6 +44,13 @@ pub struct Variable {
     pub is_unique_ptr: bool,
     #[allow(dead_code)]
     pub is_shared_ptr: bool,
+    /// Whether this variable's type is declared `union` rather than
+    /// `struct`/`class` -- every field of a union shares the same storage,
+    /// so `ir::mod`'s type classification maps this onto
+    /// `VariableType::Union` instead of `Owned`, which is what makes the
+    /// borrow checker's place-overlap check treat any two of its fields as
+    /// aliasing instead of disjoint siblings.
+    pub is_union: bool,
     #[allow(dead_code)]
     pub location: SourceLocation,
 }
@@ -77,6 +90,15 @@ pub enum Statement {
         else_branch: Option<Vec<Statement>>,
         location: SourceLocation,
     },
+    /// An `unsafe { ... }` scope nested inside an otherwise `@safe`
+    /// function: pointer operations inside it are locally permitted
+    /// instead of failing the whole function's check. `location` is the
+    /// `unsafe` label's own position, not the body's -- it's what a lint
+    /// flagging the block itself (e.g. as unnecessary) should point at.
+    UnsafeBlock {
+        statements: Vec<Statement>,
+        location: SourceLocation,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -96,6 +118,13 @@ pub enum Expression {
         op: String,
         right: Box<Expression>,
     },
+    /// `base.member` or `base->member` -- libclang's `MemberRefExpr` doesn't
+    /// distinguish the two, and for borrow-checking purposes they name the
+    /// same place either way, so both collapse to this one variant.
+    Field {
+        base: Box<Expression>,
+        member: String,
+    },
 }
 
 #[derive(Debug, Clone)]
@@ -110,8 +139,9 @@ pub struct SourceLocation {
 
 pub fn extract_function(entity: &Entity) -> Function {
     let name = entity.get_name().unwrap_or_else(|| "anonymous".to_string());
+    let qualified_name = super::safety_annotations::qualified_name(entity);
     let location = extract_location(entity);
-    
+
     let mut parameters = Vec::new();
     for child in entity.get_children() {
         if child.get_kind() == EntityKind::ParmDecl {
@@ -128,6 +158,7 @@ pub fn extract_function(entity: &Entity) -> Function {
     
     Function {
         name,
+        qualified_name,
         parameters,
         return_type,
         body,
@@ -158,7 +189,21 @@ pub fn extract_variable(entity: &Entity) -> Variable {
     
     let is_unique_ptr = type_name.contains("unique_ptr");
     let is_shared_ptr = type_name.contains("shared_ptr");
-    
+
+    // A union aliases every field onto the same storage, which the borrow
+    // checker needs to know about (see `ir::VariableType::Union`) --
+    // resolve through any reference/pointer first, since `int& r` bound to
+    // a union still aliases the union it points at, not a distinct place.
+    let record_type = if is_reference || is_pointer {
+        type_info.get_pointee_type().unwrap_or(type_info)
+    } else {
+        type_info
+    };
+    let is_union = record_type
+        .get_declaration()
+        .map(|decl| decl.get_kind() == EntityKind::UnionDecl)
+        .unwrap_or(false);
+
     Variable {
         name,
         type_name,
@@ -167,6 +212,7 @@ pub fn extract_variable(entity: &Entity) -> Variable {
         is_const,
         is_unique_ptr,
         is_shared_ptr,
+        is_union,
         location,
     }
 }
@@ -279,6 +325,19 @@ fn extract_compound_statement(entity: &Entity) -> Vec<Statement> {
                 statements.extend(extract_compound_statement(&child));
                 statements.push(Statement::ExitScope);
             }
+            EntityKind::LabelStmt if child.get_name().as_deref() == Some("unsafe") => {
+                // The dialect spells `unsafe { ... }` as a label named
+                // `unsafe` directly followed by the block, since `unsafe`
+                // isn't a real C++ keyword a label could otherwise collide
+                // with -- `unsafe: { ... }` parses as an ordinary labeled
+                // statement.
+                if let Some(body) = child.get_children().into_iter().find(|c| c.get_kind() == EntityKind::CompoundStmt) {
+                    statements.push(Statement::UnsafeBlock {
+                        statements: extract_compound_statement(&body),
+                        location: extract_location(&child),
+                    });
+                }
+            }
             EntityKind::ForStmt | EntityKind::WhileStmt | EntityKind::DoStmt => {
                 // Loop detected - add loop markers
                 statements.push(Statement::EnterLoop);
@@ -332,7 +391,7 @@ fn extract_compound_statement(entity: &Entity) -> Vec<Statement> {
     statements
 }
 
-fn extract_expression(entity: &Entity) -> Option<Expression> {
+pub(crate) fn extract_expression(entity: &Entity) -> Option<Expression> {
     match entity.get_kind() {
         EntityKind::DeclRefExpr => {
             entity.get_name().map(Expression::Variable)
@@ -403,6 +462,22 @@ fn extract_expression(entity: &Entity) -> Option<Expression> {
         EntityKind::IntegerLiteral => {
             entity.get_name().map(Expression::Literal)
         }
+        EntityKind::MemberRefExpr => {
+            // The member's own name comes off the `MemberRefExpr` entity
+            // itself; its sole child is the base expression (`obj` in both
+            // `obj.field` and `obj->field` -- libclang inserts an implicit
+            // dereference for `->` that doesn't show up as a separate node
+            // worth representing here).
+            let member = entity.get_name()?;
+            let base = entity
+                .get_children()
+                .into_iter()
+                .find_map(|c| extract_expression(&c))?;
+            Some(Expression::Field {
+                base: Box::new(base),
+                member,
+            })
+        }
         EntityKind::UnaryOperator => {
             // Check if it's address-of (&) or dereference (*)
             let children: Vec<Entity> = entity.get_children().into_iter().collect();
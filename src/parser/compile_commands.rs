@@ -0,0 +1,290 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Compiler configuration extracted from a single `compile_commands.json`
+/// entry: every include search path (in the order clang would see them,
+/// `-I`/`-isystem`/`-iquote`/`-idirafter` alike), the `-D` defines, and an
+/// overriding `-std=` if the build specified one, so the file can be
+/// parsed under the same configuration the real build uses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CompileFlags {
+    pub include_paths: Vec<PathBuf>,
+    pub defines: Vec<String>,
+    pub std: Option<String>,
+}
+
+/// Find the compilation database entry for `source_file` and extract its
+/// [`CompileFlags`]. Entries are matched by normalized absolute path (so a
+/// `directory`-relative `file` field still matches a differently-spelled
+/// but equal path), and both the `arguments` array and shell-quoted
+/// `command` string forms are understood, including `@response.txt` file
+/// expansion.
+///
+/// Returns the default (empty) [`CompileFlags`] if no entry matches, the
+/// same lenient behavior the caller already relies on for "not in the
+/// compilation database" files.
+pub fn extract_compile_flags(cc_path: &Path, source_file: &Path) -> Result<CompileFlags, String> {
+    let content = fs::read_to_string(cc_path)
+        .map_err(|e| format!("Failed to read compile_commands.json: {}", e))?;
+
+    let commands: Vec<serde_json::Value> = serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse compile_commands.json: {}", e))?;
+
+    let target = normalize_path(source_file, None);
+
+    for entry in &commands {
+        let Some(file) = entry.get("file").and_then(|f| f.as_str()) else {
+            continue;
+        };
+        let directory = entry.get("directory").and_then(|d| d.as_str());
+
+        if normalize_path(Path::new(file), directory) == target {
+            let tokens = expand_response_files(&entry_tokens(entry), directory)?;
+            return Ok(parse_flags(&tokens, directory));
+        }
+    }
+
+    Ok(CompileFlags::default())
+}
+
+/// Resolve `path` to an absolute path (relative to `directory` if given
+/// and `path` itself is relative), canonicalizing when possible so
+/// differently-spelled equal paths (`./a.cpp` vs `a.cpp`, symlinks, `..`
+/// components) compare equal.
+fn normalize_path(path: &Path, directory: Option<&str>) -> PathBuf {
+    let absolute = if path.is_absolute() {
+        path.to_path_buf()
+    } else if let Some(dir) = directory {
+        Path::new(dir).join(path)
+    } else {
+        path.to_path_buf()
+    };
+
+    absolute.canonicalize().unwrap_or(absolute)
+}
+
+/// Get an entry's argv as a flat token list: prefer the `arguments` array
+/// (already split, no shell quoting to undo) and fall back to
+/// shell-splitting `command`.
+fn entry_tokens(entry: &serde_json::Value) -> Vec<String> {
+    if let Some(arguments) = entry.get("arguments").and_then(|a| a.as_array()) {
+        arguments
+            .iter()
+            .filter_map(|v| v.as_str().map(str::to_string))
+            .collect()
+    } else if let Some(command) = entry.get("command").and_then(|c| c.as_str()) {
+        shell_split(command)
+    } else {
+        Vec::new()
+    }
+}
+
+/// Split a shell command line into arguments, honoring single/double
+/// quotes and backslash escapes the way a POSIX shell would. Compilation
+/// databases write `command` with shell quoting (unlike `arguments`),
+/// so a plain `split_whitespace` breaks on any quoted path with a space.
+fn shell_split(command: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_token = false;
+    let mut quote: Option<char> = None;
+    let mut chars = command.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if let Some(q) = quote {
+            if c == q {
+                quote = None;
+            } else if c == '\\' && q == '"' && matches!(chars.peek(), Some('"') | Some('\\') | Some('$')) {
+                current.push(chars.next().unwrap());
+            } else {
+                current.push(c);
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' => {
+                quote = Some(c);
+                in_token = true;
+            }
+            '\\' => {
+                if let Some(next) = chars.next() {
+                    current.push(next);
+                    in_token = true;
+                }
+            }
+            c if c.is_whitespace() => {
+                if in_token {
+                    tokens.push(std::mem::take(&mut current));
+                    in_token = false;
+                }
+            }
+            c => {
+                current.push(c);
+                in_token = true;
+            }
+        }
+    }
+
+    if in_token {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Expand any `@response.txt` tokens in place, recursively, resolving
+/// response file paths relative to `directory` the same way include
+/// paths are.
+fn expand_response_files(tokens: &[String], directory: Option<&str>) -> Result<Vec<String>, String> {
+    let mut expanded = Vec::new();
+
+    for token in tokens {
+        match token.strip_prefix('@') {
+            Some(response_path) => {
+                let resolved = normalize_include_path(response_path, directory);
+                let content = fs::read_to_string(&resolved)
+                    .map_err(|e| format!("Failed to read response file {}: {}", resolved.display(), e))?;
+                expanded.extend(expand_response_files(&shell_split(&content), directory)?);
+            }
+            None => expanded.push(token.clone()),
+        }
+    }
+
+    Ok(expanded)
+}
+
+fn parse_flags(tokens: &[String], directory: Option<&str>) -> CompileFlags {
+    let mut flags = CompileFlags::default();
+    let mut iter = tokens.iter().peekable();
+
+    while let Some(token) = iter.next() {
+        if token == "-isystem" || token == "-iquote" || token == "-idirafter" || token == "-I" {
+            if let Some(next) = iter.next() {
+                flags.include_paths.push(normalize_include_path(next, directory));
+            }
+        } else if let Some(path) = token
+            .strip_prefix("-isystem")
+            .or_else(|| token.strip_prefix("-iquote"))
+            .or_else(|| token.strip_prefix("-idirafter"))
+            .or_else(|| token.strip_prefix("-I"))
+        {
+            if !path.is_empty() {
+                flags.include_paths.push(normalize_include_path(path, directory));
+            }
+        } else if token == "-D" {
+            if let Some(next) = iter.next() {
+                flags.defines.push(next.clone());
+            }
+        } else if let Some(define) = token.strip_prefix("-D") {
+            flags.defines.push(define.to_string());
+        } else if let Some(std) = token.strip_prefix("-std=") {
+            flags.std = Some(std.to_string());
+        }
+    }
+
+    flags
+}
+
+/// Resolve an include/response-file path relative to the entry's
+/// `directory` field (if it's relative and a directory is known), without
+/// requiring the path to exist -- unlike [`normalize_path`], include
+/// directories are allowed to be created later in the build.
+fn normalize_include_path(raw: &str, directory: Option<&str>) -> PathBuf {
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        path
+    } else if let Some(dir) = directory {
+        Path::new(dir).join(path)
+    } else {
+        path
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use tempfile::NamedTempFile;
+
+    #[test]
+    fn test_shell_split_honors_quotes() {
+        let tokens = shell_split(r#"g++ -I"/usr/local/my include" -DFOO=1 a.cpp"#);
+        assert_eq!(tokens, vec!["g++", "-I/usr/local/my include", "-DFOO=1", "a.cpp"]);
+    }
+
+    #[test]
+    fn test_parse_flags_collects_all_include_flavors() {
+        let tokens: Vec<String> = [
+            "-Iinclude", "-isystem", "/usr/include/sys", "-iquote", "quote_dir", "-idirafter", "after_dir",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let flags = parse_flags(&tokens, Some("/proj"));
+        assert_eq!(
+            flags.include_paths,
+            vec![
+                PathBuf::from("/proj/include"),
+                PathBuf::from("/usr/include/sys"),
+                PathBuf::from("/proj/quote_dir"),
+                PathBuf::from("/proj/after_dir"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_flags_collects_defines_and_std() {
+        let tokens: Vec<String> = ["-DFOO=1", "-D", "BAR", "-std=c++20"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+
+        let flags = parse_flags(&tokens, None);
+        assert_eq!(flags.defines, vec!["FOO=1".to_string(), "BAR".to_string()]);
+        assert_eq!(flags.std, Some("c++20".to_string()));
+    }
+
+    #[test]
+    fn test_expand_response_file() {
+        let mut response_file = NamedTempFile::with_suffix(".rsp").unwrap();
+        response_file.write_all(b"-DFROM_RESPONSE_FILE -Iresponse_include").unwrap();
+        response_file.flush().unwrap();
+
+        let tokens = vec![format!("@{}", response_file.path().display())];
+        let expanded = expand_response_files(&tokens, None).unwrap();
+        assert_eq!(expanded, vec!["-DFROM_RESPONSE_FILE", "-Iresponse_include"]);
+    }
+
+    #[test]
+    fn test_extract_compile_flags_matches_entry_via_arguments() {
+        let mut source_file = NamedTempFile::with_suffix(".cpp").unwrap();
+        source_file.write_all(b"int main() { return 0; }").unwrap();
+        source_file.flush().unwrap();
+
+        let mut cc_file = NamedTempFile::with_suffix(".json").unwrap();
+        let db = serde_json::json!([{
+            "directory": "/proj",
+            "file": source_file.path().to_string_lossy(),
+            "arguments": ["g++", "-Iinclude", "-DFOO=1", "-std=c++20", "-c", "a.cpp"],
+        }]);
+        cc_file.write_all(serde_json::to_string(&db).unwrap().as_bytes()).unwrap();
+        cc_file.flush().unwrap();
+
+        let flags = extract_compile_flags(cc_file.path(), source_file.path()).unwrap();
+        assert_eq!(flags.include_paths, vec![PathBuf::from("/proj/include")]);
+        assert_eq!(flags.defines, vec!["FOO=1".to_string()]);
+        assert_eq!(flags.std, Some("c++20".to_string()));
+    }
+
+    #[test]
+    fn test_extract_compile_flags_no_matching_entry() {
+        let mut cc_file = NamedTempFile::with_suffix(".json").unwrap();
+        cc_file.write_all(b"[]").unwrap();
+        cc_file.flush().unwrap();
+
+        let flags = extract_compile_flags(cc_file.path(), Path::new("/does/not/exist.cpp")).unwrap();
+        assert_eq!(flags, CompileFlags::default());
+    }
+}
@@ -1,193 +1,379 @@
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
-use std::fs;
-use regex::Regex;
-use clang::{Clang, Index};
+use clang::{Clang, Entity, EntityKind, Index};
 
 use super::annotations::{FunctionSignature, extract_annotations};
+use super::lifetime_elision::{elide_missing_lifetimes, elide_signature, ElisionFailure};
 
 /// Cache for storing function signatures from header files
-#[derive(Debug, Default)]
 pub struct HeaderCache {
     /// Map from function name to its lifetime signature
     signatures: HashMap<String, FunctionSignature>,
+    /// Functions whose return-reference lifetime elision was ambiguous
+    /// (multiple unrelated input lifetimes, no `@lifetime` annotation to
+    /// fall back on), collected so callers can surface a single diagnostic
+    /// instead of silently guessing.
+    elision_failures: Vec<ElisionFailure>,
     /// Paths of headers that have been processed
     processed_headers: Vec<PathBuf>,
     /// Include paths to search for headers
     include_paths: Vec<PathBuf>,
+    /// A single Clang instance reused for every parse. `Clang::new()` can
+    /// only succeed once per process, so re-creating it per header (as the
+    /// old implementation did) would fail on the second header parsed.
+    clang: Clang,
+    /// If non-empty, only qualified names matching one of these globs have
+    /// their signatures extracted; everything else is skipped.
+    allowed_names: Vec<String>,
+    /// Qualified names matching one of these globs are never extracted,
+    /// even if they also match `allowed_names`.
+    blocked_names: Vec<String>,
+    /// If non-empty, only headers whose path matches one of these globs are
+    /// parsed at all.
+    allowed_header_globs: Vec<String>,
+    /// Headers whose path matches one of these globs are never parsed.
+    blocked_header_globs: Vec<String>,
+}
+
+impl Default for HeaderCache {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The `[headers]` table of a `rustycpp.toml`-style config, supplying
+/// [`HeaderCache::set_allow_list`]/[`HeaderCache::set_block_list`] the same
+/// way `[allowlist]` supplies [`super::safety_annotations::CallAllowlist`]
+/// -- a project scopes header parsing through config instead of code.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderAllowBlockConfig {
+    pub allow_names: Vec<String>,
+    pub allow_header_globs: Vec<String>,
+    pub block_names: Vec<String>,
+    pub block_header_globs: Vec<String>,
+}
+
+impl HeaderAllowBlockConfig {
+    /// Parse the `[headers]` table: four string arrays, `allow_names`,
+    /// `allow_header_globs`, `block_names`, `block_header_globs`. Same
+    /// minimal line-based reader as `CallAllowlist::from_toml`, for the
+    /// same reason -- this isn't a general TOML parser, just a reader for
+    /// this one table's shape.
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let mut config = Self::default();
+        let mut in_table = false;
+
+        for (line_no, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_table = line.trim_start_matches('[').trim_end_matches(']') == "headers";
+                continue;
+            }
+            if !in_table {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("malformed headers line {}: {:?}", line_no + 1, raw_line));
+            };
+            let entries = super::safety_annotations::parse_toml_string_array(value.trim())
+                .ok_or_else(|| format!("expected a string array on headers line {}: {:?}", line_no + 1, raw_line))?;
+
+            match key.trim() {
+                "allow_names" => config.allow_names = entries,
+                "allow_header_globs" => config.allow_header_globs = entries,
+                "block_names" => config.block_names = entries,
+                "block_header_globs" => config.block_header_globs = entries,
+                other => return Err(format!("unknown headers key {:?} on line {}", other, line_no + 1)),
+            }
+        }
+
+        Ok(config)
+    }
 }
 
 impl HeaderCache {
     pub fn new() -> Self {
-        Self::default()
+        Self {
+            signatures: HashMap::new(),
+            elision_failures: Vec::new(),
+            processed_headers: Vec::new(),
+            include_paths: Vec::new(),
+            clang: Clang::new().expect("Failed to initialize Clang"),
+            allowed_names: Vec::new(),
+            blocked_names: Vec::new(),
+            allowed_header_globs: Vec::new(),
+            blocked_header_globs: Vec::new(),
+        }
     }
-    
+
     /// Set the include paths for header file resolution
     pub fn set_include_paths(&mut self, paths: Vec<PathBuf>) {
         self.include_paths = paths;
     }
-    
+
+    /// Restrict signature extraction to qualified names/header paths that
+    /// match one of these globs (`autocxx`-style `allow!`). An empty list
+    /// means "no restriction".
+    pub fn set_allow_list(&mut self, names: Vec<String>, header_path_globs: Vec<String>) {
+        self.allowed_names = names;
+        self.allowed_header_globs = header_path_globs;
+    }
+
+    /// Exclude qualified names/header paths matching one of these globs from
+    /// signature extraction (`autocxx`-style `block!`), even if they also
+    /// match the allow list.
+    pub fn set_block_list(&mut self, names: Vec<String>, header_path_globs: Vec<String>) {
+        self.blocked_names = names;
+        self.blocked_header_globs = header_path_globs;
+    }
+
+    fn is_header_allowed(&self, header_path: &Path) -> bool {
+        let path_str = header_path.to_string_lossy();
+
+        if self.blocked_header_globs.iter().any(|g| super::glob_match(g, &path_str)) {
+            return false;
+        }
+        if self.allowed_header_globs.is_empty() {
+            return true;
+        }
+        self.allowed_header_globs.iter().any(|g| super::glob_match(g, &path_str))
+    }
+
+    fn is_name_allowed(&self, qualified_name: &str) -> bool {
+        if self.blocked_names.iter().any(|g| super::glob_match(g, qualified_name)) {
+            return false;
+        }
+        if self.allowed_names.is_empty() {
+            return true;
+        }
+        self.allowed_names.iter().any(|g| super::glob_match(g, qualified_name))
+    }
+
     /// Get a function signature by name
     pub fn get_signature(&self, func_name: &str) -> Option<&FunctionSignature> {
         self.signatures.get(func_name)
     }
-    
+
+    /// Every cached function signature, for passes that need to scan all of
+    /// them rather than look one up by name.
+    pub fn signatures(&self) -> impl Iterator<Item = &FunctionSignature> {
+        self.signatures.values()
+    }
+
+    /// Functions whose return-reference lifetime elision was ambiguous and
+    /// so needs an explicit `@lifetime` annotation to disambiguate.
+    pub fn elision_failures(&self) -> &[ElisionFailure] {
+        &self.elision_failures
+    }
+
     /// Parse a header file and extract all annotated function signatures
     pub fn parse_header(&mut self, header_path: &Path) -> Result<(), String> {
         // Skip if already processed
         if self.processed_headers.iter().any(|p| p == header_path) {
             return Ok(());
         }
-        
-        // Initialize Clang
-        let clang = Clang::new()
-            .map_err(|e| format!("Failed to initialize Clang: {:?}", e))?;
-        let index = Index::new(&clang, false, false);
-        
+
+        // A blocked header (e.g. a third-party system header) is never
+        // parsed for signatures at all.
+        if !self.is_header_allowed(header_path) {
+            return Ok(());
+        }
+
+        let index = Index::new(&self.clang, false, false);
+
         // Build arguments with include paths
         let mut args = vec!["-std=c++17".to_string(), "-xc++".to_string()];
         for include_path in &self.include_paths {
             args.push(format!("-I{}", include_path.display()));
         }
-        
+
         // Parse the header file
         let tu = index
             .parser(header_path)
             .arguments(&args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
             .parse()
             .map_err(|e| format!("Failed to parse header {}: {:?}", header_path.display(), e))?;
-        
+
         // Extract function signatures with annotations
         let root = tu.get_entity();
         self.visit_entity_for_signatures(&root);
-        
+
         self.processed_headers.push(header_path.to_path_buf());
         Ok(())
     }
-    
-    /// Parse headers from a C++ source file's includes
+
+    /// Parse every header transitively included by a C++ source file. Unlike
+    /// a regex scan, this walks the real preprocessor include graph, so it
+    /// naturally respects `#ifdef` guards, commented-out includes, and
+    /// macro-expanded paths.
     pub fn parse_includes_from_source(&mut self, cpp_file: &Path) -> Result<(), String> {
-        let content = fs::read_to_string(cpp_file)
-            .map_err(|e| format!("Failed to read {}: {}", cpp_file.display(), e))?;
-        
-        let (quoted_includes, angle_includes) = extract_includes(&content);
-        
-        // Process quoted includes (search relative to source file first)
-        for include_path in quoted_includes {
-            if let Some(resolved) = self.resolve_include(&include_path, cpp_file, true) {
-                self.parse_header(&resolved)?;
-            }
-        }
-        
-        // Process angle bracket includes (search include paths only)
-        for include_path in angle_includes {
-            if let Some(resolved) = self.resolve_include(&include_path, cpp_file, false) {
-                self.parse_header(&resolved)?;
-            }
+        let resolved = self.resolve_transitive_includes(cpp_file)?;
+
+        for header in resolved {
+            self.parse_header(&header)?;
         }
-        
+
         Ok(())
     }
-    
-    /// Resolve an include path using standard C++ include resolution rules
-    fn resolve_include(&self, include_path: &str, source_file: &Path, search_source_dir: bool) -> Option<PathBuf> {
-        // For quoted includes, first try relative to the source file
-        if search_source_dir {
-            if let Some(parent) = source_file.parent() {
-                let local_path = parent.join(include_path);
-                if local_path.exists() {
-                    return Some(local_path);
-                }
-            }
-        }
-        
-        // Search in include paths
-        for include_dir in &self.include_paths {
-            let full_path = include_dir.join(include_path);
-            if full_path.exists() {
-                return Some(full_path);
-            }
-        }
-        
-        // Try as absolute or relative to current directory
-        let path = PathBuf::from(include_path);
-        if path.exists() {
-            return Some(path);
+
+    /// Parse `cpp_file` with `detailed_preprocessing_record` and enumerate
+    /// its `InclusionDirective` entities to get the actual set of headers
+    /// the preprocessor resolved and included.
+    fn resolve_transitive_includes(&self, cpp_file: &Path) -> Result<Vec<PathBuf>, String> {
+        let index = Index::new(&self.clang, false, false);
+
+        let mut args = vec!["-std=c++17".to_string(), "-xc++".to_string()];
+        for include_path in &self.include_paths {
+            args.push(format!("-I{}", include_path.display()));
         }
-        
-        None
+
+        let tu = index
+            .parser(cpp_file)
+            .arguments(&args.iter().map(|s| s.as_str()).collect::<Vec<_>>())
+            .detailed_preprocessing_record(true)
+            .parse()
+            .map_err(|e| format!("Failed to parse {} for includes: {:?}", cpp_file.display(), e))?;
+
+        let mut includes = Vec::new();
+        collect_inclusion_directives(&tu.get_entity(), &mut includes);
+        Ok(includes)
     }
-    
+
     fn visit_entity_for_signatures(&mut self, entity: &clang::Entity) {
-        use clang::EntityKind;
-        
         match entity.get_kind() {
             EntityKind::FunctionDecl | EntityKind::Method => {
-                if let Some(sig) = extract_annotations(entity) {
-                    self.signatures.insert(sig.name.clone(), sig);
+                if self.is_name_allowed(&super::safety_annotations::qualified_name(entity)) {
+                    match extract_annotations(entity) {
+                        // An explicit `@lifetime` comment always wins, but
+                        // it may not cover every parameter (or any, if the
+                        // comment was just `@safe`) -- elide whatever it
+                        // left unannotated instead of leaving those
+                        // reference parameters untracked.
+                        Some(mut sig) => {
+                            if let Some(failure) = elide_missing_lifetimes(entity, &mut sig) {
+                                self.elision_failures.push(failure);
+                            }
+                            self.signatures.insert(sig.name.clone(), sig);
+                        }
+                        // No annotation at all: fall back to eliding the
+                        // whole signature, so common cases don't need one.
+                        None => {
+                            if let Some((sig, failure)) = elide_signature(entity) {
+                                if let Some(failure) = failure {
+                                    self.elision_failures.push(failure);
+                                }
+                                self.signatures.insert(sig.name.clone(), sig);
+                            }
+                        }
+                    }
                 }
             }
             _ => {}
         }
-        
+
         // Recursively visit children
         for child in entity.get_children() {
             self.visit_entity_for_signatures(&child);
         }
     }
-    
+
     /// Check if any signatures are cached
     pub fn has_signatures(&self) -> bool {
         !self.signatures.is_empty()
     }
 }
 
-/// Extract include paths from C++ source, separating quoted and angle bracket includes
-fn extract_includes(content: &str) -> (Vec<String>, Vec<String>) {
-    let mut quoted_includes = Vec::new();
-    let mut angle_includes = Vec::new();
-    
-    // Match quoted includes: #include "file.h"
-    let quoted_re = Regex::new(r#"#include\s*"([^"]+)""#).unwrap();
-    for cap in quoted_re.captures_iter(content) {
-        if let Some(path) = cap.get(1) {
-            quoted_includes.push(path.as_str().to_string());
+/// Collect the resolved file path of every `InclusionDirective` entity in
+/// the translation unit, in preprocessing order.
+fn collect_inclusion_directives(entity: &Entity, includes: &mut Vec<PathBuf>) {
+    if entity.get_kind() == EntityKind::InclusionDirective {
+        if let Some(file) = entity.get_file() {
+            includes.push(file.get_path());
         }
     }
-    
-    // Match angle bracket includes: #include <file.h>
-    let angle_re = Regex::new(r#"#include\s*<([^>]+)>"#).unwrap();
-    for cap in angle_re.captures_iter(content) {
-        if let Some(path) = cap.get(1) {
-            angle_includes.push(path.as_str().to_string());
-        }
+
+    for child in entity.get_children() {
+        collect_inclusion_directives(&child, includes);
     }
-    
-    (quoted_includes, angle_includes)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    
+    use tempfile::NamedTempFile;
+    use std::io::Write;
+
+    fn create_temp_header(content: &str) -> NamedTempFile {
+        let mut file = NamedTempFile::with_suffix(".h").unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        file.flush().unwrap();
+        file
+    }
+
+    #[test]
+    fn test_parse_header_reuses_clang_across_calls() {
+        let header_a = create_temp_header("void func_a();\n");
+        let header_b = create_temp_header("void func_b();\n");
+
+        let mut cache = HeaderCache::new();
+
+        // The old implementation created a fresh Clang per header, which
+        // fails with "already exists" on the second parse; this must
+        // succeed for both headers from a single HeaderCache.
+        match cache.parse_header(header_a.path()) {
+            Ok(()) => {
+                assert!(cache.parse_header(header_b.path()).is_ok());
+            }
+            Err(e) if e.contains("Failed to parse header") => {
+                // Tolerate libclang being unavailable in this environment.
+            }
+            Err(e) => panic!("Unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_header_allow_block_config_from_toml_parses_lists() {
+        let toml = r#"
+[headers]
+allow_names = ["myapp::safe_fn"]
+allow_header_globs = ["include/myapp/*"]
+block_names = ["legacy::dangerous"]
+block_header_globs = ["include/third_party/*"]
+"#;
+
+        let config = HeaderAllowBlockConfig::from_toml(toml).unwrap();
+        assert_eq!(config.allow_names, vec!["myapp::safe_fn".to_string()]);
+        assert_eq!(config.allow_header_globs, vec!["include/myapp/*".to_string()]);
+        assert_eq!(config.block_names, vec!["legacy::dangerous".to_string()]);
+        assert_eq!(config.block_header_globs, vec!["include/third_party/*".to_string()]);
+    }
+
+    #[test]
+    fn test_header_allow_block_config_from_toml_rejects_unknown_key() {
+        let toml = "[headers]\nnot_a_real_key = [\"x\"]\n";
+        assert!(HeaderAllowBlockConfig::from_toml(toml).is_err());
+    }
+
     #[test]
-    fn test_extract_includes() {
-        let content = r#"
-#include "user.h"
-#include "data.h"
-#include <iostream>
-#include <vector>
-#include "utils/helper.h"
-        "#;
-        
-        let (quoted, angle) = extract_includes(content);
-        assert_eq!(quoted.len(), 3);
-        assert_eq!(quoted[0], "user.h");
-        assert_eq!(quoted[1], "data.h");
-        assert_eq!(quoted[2], "utils/helper.h");
-        
-        assert_eq!(angle.len(), 2);
-        assert_eq!(angle[0], "iostream");
-        assert_eq!(angle[1], "vector");
-    }
-}
\ No newline at end of file
+    fn test_safe_only_annotation_still_gets_return_lifetime_elided() {
+        let header = create_temp_header(
+            "// @safe\nconst int& pick(const int& value);\n",
+        );
+
+        let mut cache = HeaderCache::new();
+        match cache.parse_header(header.path()) {
+            Ok(()) => {
+                let sig = cache.get_signature("pick").expect("signature should be cached");
+                assert!(sig.return_lifetime.is_some(), "return lifetime should be elided: {:?}", sig);
+            }
+            Err(e) if e.contains("Failed to parse header") => {
+                // Tolerate libclang being unavailable in this environment.
+            }
+            Err(e) => panic!("Unexpected error: {}", e),
+        }
+    }
+}
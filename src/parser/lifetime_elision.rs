@@ -0,0 +1,166 @@
+use clang::{Entity, EntityKind, Type, TypeKind};
+
+use super::annotations::{FunctionSignature, Lifetime, LifetimeAnnotation};
+
+/// Lifetime parameter names assigned to elided inputs, in order. Mirrors the
+/// positional convention `analysis::lifetime_checker` already uses for
+/// explicit `@lifetime` annotations.
+const LIFETIME_NAMES: &[&str] = &["a", "b", "c", "d", "e", "f", "g", "h"];
+
+/// Why an elided output (reference) lifetime couldn't be resolved, mirroring
+/// rustc's `ElisionFailureInfo`: which function it was, and which input
+/// lifetimes were in scope, so a diagnostic can tell the user exactly which
+/// one to name explicitly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ElisionFailure {
+    pub function: String,
+    pub candidate_lifetimes: Vec<String>,
+}
+
+/// Elide a [`FunctionSignature`] straight from `entity`'s parameter and
+/// return types, the way Rust elides a `fn` signature with no explicit
+/// lifetime parameters, adapted to C++:
+///
+/// 1. every reference parameter gets a fresh, distinct input lifetime;
+/// 2. if there is exactly one input lifetime, it is assigned to every
+///    elided output (reference) lifetime;
+/// 3. for a non-static method, the lifetime of the implicit `this`/`*this`
+///    is an extra input lifetime and is preferred for elided outputs
+///    (mirroring Rust's elided `&self` rule).
+///
+/// Returns `None` if `entity` isn't a function/method declaration. When the
+/// function returns a reference but no rule above resolves its lifetime
+/// (multiple, unrelated input lifetimes), the returned signature leaves
+/// `return_lifetime` unset and the second element explains why.
+pub fn elide_signature(entity: &Entity) -> Option<(FunctionSignature, Option<ElisionFailure>)> {
+    if !matches!(entity.get_kind(), EntityKind::FunctionDecl | EntityKind::Method) {
+        return None;
+    }
+    let name = entity.get_name()?;
+
+    let mut param_lifetimes = vec![None; count_params(entity)];
+    let mut return_lifetime = None;
+    let failure = elide_missing(entity, &name, &mut param_lifetimes, &mut return_lifetime);
+
+    Some((
+        FunctionSignature {
+            name,
+            return_lifetime,
+            param_lifetimes,
+            lifetime_bounds: Vec::new(),
+            safety: None,
+        },
+        failure,
+    ))
+}
+
+/// Fill in any reference parameter or return lifetime `sig` left
+/// unannotated -- e.g. its `@lifetime` comment only covered some
+/// parameters, or there was no `@lifetime` clause at all (just `@safe`) --
+/// using the same three elision rules [`elide_signature`] applies when
+/// starting from nothing. A slot that already holds an explicit annotation
+/// is never overwritten.
+pub fn elide_missing_lifetimes(entity: &Entity, sig: &mut FunctionSignature) -> Option<ElisionFailure> {
+    if !matches!(entity.get_kind(), EntityKind::FunctionDecl | EntityKind::Method) {
+        return None;
+    }
+    // The `@lifetime` comment's parameter list (if any) may be shorter than
+    // the real signature -- e.g. it only named the first parameter.
+    sig.param_lifetimes.resize(count_params(entity), None);
+    elide_missing(entity, &sig.name, &mut sig.param_lifetimes, &mut sig.return_lifetime)
+}
+
+fn count_params(entity: &Entity) -> usize {
+    entity
+        .get_children()
+        .into_iter()
+        .filter(|c| c.get_kind() == EntityKind::ParmDecl)
+        .count()
+}
+
+/// Shared core of [`elide_signature`] and [`elide_missing_lifetimes`]: walk
+/// `entity`'s parameters, filling every unfilled reference parameter slot
+/// with a fresh input lifetime (rule 1), then -- unless `return_lifetime`
+/// is already set -- resolve an elided return lifetime from the receiver
+/// (rule 3) or the sole input lifetime (rule 2).
+fn elide_missing(
+    entity: &Entity,
+    name: &str,
+    param_lifetimes: &mut [Option<LifetimeAnnotation>],
+    return_lifetime: &mut Option<LifetimeAnnotation>,
+) -> Option<ElisionFailure> {
+    let params: Vec<Entity> = entity
+        .get_children()
+        .into_iter()
+        .filter(|c| c.get_kind() == EntityKind::ParmDecl)
+        .collect();
+
+    let mut input_lifetimes: Vec<String> = Vec::new();
+    for (param, slot) in params.iter().zip(param_lifetimes.iter_mut()) {
+        match slot {
+            Some(LifetimeAnnotation::Ref(lifetime) | LifetimeAnnotation::MutRef(lifetime)) => {
+                input_lifetimes.push(lifetime.to_string());
+            }
+            Some(_) => {} // already annotated owned/bare-lifetime, not an input
+            None => {
+                if let Some(ty) = param.get_type().filter(is_reference_type) {
+                    let lifetime = lifetime_name(input_lifetimes.len());
+                    input_lifetimes.push(lifetime.clone());
+                    *slot = Some(reference_annotation(&ty, lifetime));
+                }
+            }
+        }
+    }
+
+    // Rule 3: a non-static method's implicit `this` is an extra input
+    // lifetime, and is preferred for elided outputs.
+    let self_lifetime = (entity.get_kind() == EntityKind::Method && !entity.is_static_method())
+        .then(|| lifetime_name(input_lifetimes.len()));
+    if let Some(lifetime) = &self_lifetime {
+        input_lifetimes.push(lifetime.clone());
+    }
+
+    if return_lifetime.is_some() {
+        return None; // an explicit return lifetime is never overwritten
+    }
+
+    match entity.get_result_type().filter(is_reference_type) {
+        Some(ty) => match self_lifetime.or_else(|| match input_lifetimes.as_slice() {
+            [single] => Some(single.clone()),
+            _ => None,
+        }) {
+            Some(lifetime) => {
+                *return_lifetime = Some(reference_annotation(&ty, lifetime));
+                None
+            }
+            None => Some(ElisionFailure {
+                function: name.to_string(),
+                candidate_lifetimes: input_lifetimes,
+            }),
+        },
+        None => None,
+    }
+}
+
+fn lifetime_name(index: usize) -> String {
+    LIFETIME_NAMES
+        .get(index)
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| format!("l{}", index))
+}
+
+fn is_reference_type(ty: &Type) -> bool {
+    matches!(ty.get_kind(), TypeKind::LValueReference | TypeKind::RValueReference)
+}
+
+fn reference_annotation(ty: &Type, lifetime: String) -> LifetimeAnnotation {
+    let is_const = ty
+        .get_pointee_type()
+        .map(|pointee| pointee.is_const_qualified())
+        .unwrap_or(false);
+    if is_const {
+        LifetimeAnnotation::Ref(Lifetime::named(lifetime))
+    } else {
+        LifetimeAnnotation::MutRef(Lifetime::named(lifetime))
+    }
+}
@@ -3,7 +3,9 @@ use std::path::Path;
 
 pub mod ast_visitor;
 pub mod annotations;
+pub mod compile_commands;
 pub mod header_cache;
+pub mod lifetime_elision;
 pub mod safety_annotations;
 
 pub use ast_visitor::{CppAst, Function, Statement, Expression};
@@ -25,15 +27,28 @@ pub fn parse_cpp_file_with_includes(path: &Path, include_paths: &[std::path::Pat
 }
 
 pub fn parse_cpp_file_with_includes_and_defines(path: &Path, include_paths: &[std::path::PathBuf], defines: &[String]) -> Result<CppAst, String> {
+    parse_cpp_file_with_config(path, include_paths, defines, None)
+}
+
+/// Parse with the full set of flags a `compile_commands.json` entry can
+/// carry: include paths, `-D` defines, and an overriding `-std=` (falling
+/// back to `-std=c++17` when the build didn't specify one), so the file
+/// parses under the same configuration the real build uses.
+pub fn parse_cpp_file_with_config(
+    path: &Path,
+    include_paths: &[std::path::PathBuf],
+    defines: &[String],
+    std: Option<&str>,
+) -> Result<CppAst, String> {
     // Initialize Clang
     let clang = Clang::new()
         .map_err(|e| format!("Failed to initialize Clang: {:?}", e))?;
-    
+
     let index = Index::new(&clang, false, false);
-    
+
     // Build arguments with include paths and defines
     let mut args = vec![
-        "-std=c++17".to_string(), 
+        format!("-std={}", std.unwrap_or("c++17")),
         "-xc++".to_string(),
         // Add flags to make parsing more lenient
         "-fno-delayed-template-parsing".to_string(),
@@ -163,6 +178,35 @@ fn visit_entity(entity: &Entity, ast: &mut CppAst, visited_files: &mut std::coll
     }
 }
 
+/// Minimal glob matcher supporting `*` wildcards, shared by the allow/block
+/// list checks in `HeaderCache` and `SafetyContext`.
+pub(crate) fn glob_match(pattern: &str, value: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    if parts.len() == 1 {
+        return pattern == value;
+    }
+
+    let mut pos = 0;
+    for (i, part) in parts.iter().enumerate() {
+        if part.is_empty() {
+            continue;
+        }
+        if i == 0 {
+            if !value[pos..].starts_with(part) {
+                return false;
+            }
+            pos += part.len();
+        } else if i == parts.len() - 1 {
+            return value[pos..].ends_with(part);
+        } else if let Some(found) = value[pos..].find(part) {
+            pos += found + part.len();
+        } else {
+            return false;
+        }
+    }
+    true
+}
+
 /// Check if the file has @safe annotation at the beginning
 #[allow(dead_code)]
 pub fn check_file_safety_annotation(path: &Path) -> Result<bool, String> {
@@ -311,11 +355,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("third-party/*", "third-party/erpc/rpc.h"));
+        assert!(glob_match("*.h", "include/widget.h"));
+        assert!(glob_match("include/*.h", "include/widget.h"));
+        assert!(!glob_match("include/*.h", "src/widget.cpp"));
+        assert!(glob_match("myapp::Widget::*", "myapp::Widget::run"));
+        assert!(glob_match("exact", "exact"));
+        assert!(!glob_match("exact", "not_exact"));
+    }
+
     #[test]
     fn test_parse_invalid_file() {
         let temp_dir = tempfile::tempdir().unwrap();
         let invalid_path = temp_dir.path().join("nonexistent.cpp");
-        
+
         let result = parse_cpp_file(&invalid_path);
         assert!(result.is_err());
     }
@@ -1,7 +1,7 @@
 use std::path::Path;
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{BufRead, BufReader};
-use clang::Entity;
+use clang::{Clang, Entity, EntityKind, Index};
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SafetyMode {
@@ -10,10 +10,175 @@ pub enum SafetyMode {
     Default, // Use parent context or default (unsafe)
 }
 
+/// A translation-unit-wide policy, set once via a `// @safe-policy: ...`
+/// directive instead of annotating every function individually.
+///
+/// Modeled after autocxx's `UnsafePolicy`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SafetyPolicy {
+    /// Every function is checked-safe unless explicitly marked `@unsafe`.
+    AllFunctionsSafe,
+    /// Every function is unchecked unless explicitly marked `@safe` (the
+    /// historical default behavior of this checker).
+    AllFunctionsUnsafe,
+    /// Every function is checked-safe by default, but any function whose
+    /// cached signature carries a raw pointer/reference parameter must have
+    /// an explicit lifetime annotation for that parameter; otherwise it is
+    /// flagged instead of silently skipped.
+    ReferencesWrapped,
+    /// Whether a *called* function counts as safe is decided by the
+    /// project's own [`CallAllowlist`] (`SafetyContext::allowlist`) instead
+    /// of this crate's hardcoded, admittedly-wrong guess (the old
+    /// whitelist called `gets` and `strcpy` safe). Modeled on autocxx's
+    /// policy enum, same as the other variants here.
+    AllowlistedSafe,
+}
+
+impl SafetyPolicy {
+    fn from_directive(value: &str) -> Option<Self> {
+        match value.trim() {
+            "all-safe" | "all_functions_safe" => Some(SafetyPolicy::AllFunctionsSafe),
+            "all-unsafe" | "all_functions_unsafe" => Some(SafetyPolicy::AllFunctionsUnsafe),
+            "references-wrapped" | "references_wrapped" => Some(SafetyPolicy::ReferencesWrapped),
+            "allowlist" | "allowlisted-safe" | "allowlisted_safe" => Some(SafetyPolicy::AllowlistedSafe),
+            _ => None,
+        }
+    }
+}
+
+/// The explicit safe/unsafe call lists a project supplies for
+/// [`SafetyPolicy::AllowlistedSafe`], typically loaded once from a
+/// `rustycpp.toml` via [`CallAllowlist::from_toml`] and attached to the
+/// `SafetyContext` for the whole run.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CallAllowlist {
+    /// Exact function names considered safe to call from checked code.
+    pub safe_functions: Vec<String>,
+    /// Namespace/prefix globs (matched with the same [`super::glob_match`]
+    /// the block-list uses) whose members are all considered safe.
+    pub safe_namespaces: Vec<String>,
+    /// Function names that are never safe, even if `safe_functions` or a
+    /// `safe_namespaces` glob would otherwise cover them -- lets a project
+    /// force `gets`/`strcpy` unsafe without having to recreate the rest of
+    /// the list from scratch.
+    pub banned_functions: Vec<String>,
+}
+
+impl CallAllowlist {
+    /// Whether `func_name` is safe to call from checked code under this
+    /// allowlist. `banned_functions` always wins, even over an exact
+    /// `safe_functions` entry, so a project can carve out an exception to
+    /// its own namespace allowlist.
+    pub fn permits(&self, func_name: &str) -> bool {
+        if self.banned_functions.iter().any(|name| name == func_name) {
+            return false;
+        }
+        self.safe_functions.iter().any(|name| name == func_name)
+            || self.safe_namespaces.iter().any(|glob| super::glob_match(glob, func_name))
+    }
+
+    /// Parse the `[allowlist]` table of a `rustycpp.toml`-style config
+    /// file: three string arrays, `safe_functions`, `safe_namespaces`, and
+    /// `banned_functions`. This is deliberately a minimal line-based reader
+    /// for exactly that shape, not a general TOML parser -- in keeping
+    /// with how the rest of this module reads its own `// @...` directives
+    /// by scanning lines rather than pulling in a parsing crate.
+    pub fn from_toml(contents: &str) -> Result<Self, String> {
+        let mut allowlist = Self::default();
+        let mut in_table = false;
+
+        for (line_no, raw_line) in contents.lines().enumerate() {
+            let line = raw_line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_table = line.trim_start_matches('[').trim_end_matches(']') == "allowlist";
+                continue;
+            }
+            if !in_table {
+                continue;
+            }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("malformed allowlist line {}: {:?}", line_no + 1, raw_line));
+            };
+            let entries = parse_toml_string_array(value.trim())
+                .ok_or_else(|| format!("expected a string array on allowlist line {}: {:?}", line_no + 1, raw_line))?;
+
+            match key.trim() {
+                "safe_functions" => allowlist.safe_functions = entries,
+                "safe_namespaces" => allowlist.safe_namespaces = entries,
+                "banned_functions" => allowlist.banned_functions = entries,
+                other => return Err(format!("unknown allowlist key {:?} on line {}", other, line_no + 1)),
+            }
+        }
+
+        Ok(allowlist)
+    }
+}
+
+/// Parse a TOML-style `["a", "b", "c"]` array literal of plain strings.
+/// Returns `None` if `value` isn't bracketed, which the caller turns into a
+/// parse error with the offending line attached. `pub(crate)` so
+/// `HeaderCache`'s own minimal `[headers]` table reader
+/// ([`super::header_cache::HeaderAllowBlockConfig::from_toml`]) doesn't have
+/// to reimplement the same array syntax.
+pub(crate) fn parse_toml_string_array(value: &str) -> Option<Vec<String>> {
+    let inner = value.strip_prefix('[')?.strip_suffix(']')?;
+    Some(
+        inner
+            .split(',')
+            .map(str::trim)
+            .filter(|entry| !entry.is_empty())
+            .map(|entry| entry.trim_matches('"').to_string())
+            .collect(),
+    )
+}
+
 #[derive(Debug, Clone)]
 pub struct SafetyContext {
     pub file_default: SafetyMode,
     pub function_overrides: Vec<(String, SafetyMode)>, // Function name -> safety mode
+    /// Translation-unit-wide policy set via `// @safe-policy: ...`, if any.
+    pub policy: Option<SafetyPolicy>,
+    /// Qualified-name globs that are never checked, regardless of any
+    /// `@safe` namespace/function override or policy (`autocxx`-style
+    /// `block!`). Lets users scope the checker to one subsystem of a large
+    /// codebase without annotating every transitively included function.
+    pub blocked_functions: Vec<String>,
+    /// Source-location-scoped `@safe`/`@unsafe` regions: functions and the
+    /// `{}` blocks nested inside them. Unlike `function_overrides`, which
+    /// only resolves a whole function by name, this lets a single
+    /// `@unsafe` block inside an otherwise-`@safe` function opt just that
+    /// block out of checking (and vice versa), mirroring Rust's
+    /// `unsafe {}` inside safe code. Queried by [`Self::is_safe_at`].
+    pub regions: Vec<SafetyRegion>,
+    /// The project's explicit safe/unsafe call lists, consulted by
+    /// `is_function_safe` in place of the crate's own hardcoded guess when
+    /// `policy` is [`SafetyPolicy::AllowlistedSafe`]. Empty by default.
+    pub allowlist: CallAllowlist,
+}
+
+/// A `@safe`/`@unsafe` annotation's effective range, in source lines
+/// (inclusive). Regions can nest -- a block inside a function has its own,
+/// narrower region -- so [`SafetyContext::is_safe_at`] resolves the
+/// *innermost* one enclosing a given line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SafetyRegion {
+    pub start_line: u32,
+    pub end_line: u32,
+    pub mode: SafetyMode,
+}
+
+impl SafetyRegion {
+    fn contains(&self, line: u32) -> bool {
+        self.start_line <= line && line <= self.end_line
+    }
+
+    fn span(&self) -> u32 {
+        self.end_line - self.start_line
+    }
 }
 
 
@@ -22,163 +187,280 @@ impl SafetyContext {
         Self {
             file_default: SafetyMode::Default,
             function_overrides: Vec::new(),
+            policy: None,
+            blocked_functions: Vec::new(),
+            regions: Vec::new(),
+            allowlist: CallAllowlist::default(),
         }
     }
-    
+
+    /// Resolve whether `line` falls in checked (`@safe`) code, by the
+    /// innermost enclosing region -- the one with the smallest span, since
+    /// a nested block's region is always contained within its function's.
+    /// Falls back to `file_default` when no region encloses `line` at all.
+    pub fn is_safe_at(&self, line: u32) -> bool {
+        self.regions
+            .iter()
+            .filter(|region| region.contains(line))
+            .min_by_key(|region| region.span())
+            .map(|region| region.mode == SafetyMode::Safe)
+            .unwrap_or(self.file_default == SafetyMode::Safe)
+    }
+
+    /// Restrict checking so that any function whose qualified name matches
+    /// one of these globs is never checked, even under a `@safe` namespace.
+    pub fn set_block_list(&mut self, blocked_functions: Vec<String>) {
+        self.blocked_functions = blocked_functions;
+    }
+
+    /// Supply the project's explicit safe/unsafe call lists for
+    /// [`SafetyPolicy::AllowlistedSafe`], e.g. after loading one with
+    /// [`CallAllowlist::from_toml`].
+    pub fn set_allowlist(&mut self, allowlist: CallAllowlist) {
+        self.allowlist = allowlist;
+    }
+
     /// Check if a specific function should be checked
     pub fn should_check_function(&self, func_name: &str) -> bool {
+        if self.blocked_functions.iter().any(|g| super::glob_match(g, func_name)) {
+            return false;
+        }
+
         // First check for function-specific override
         for (name, mode) in &self.function_overrides {
             if name == func_name {
                 return *mode == SafetyMode::Safe;
             }
         }
-        
-        // Fall back to file default
-        self.file_default == SafetyMode::Safe
+
+        // A translation-unit policy takes over from the plain file default
+        // when no per-function override applies.
+        match self.policy {
+            Some(SafetyPolicy::AllFunctionsSafe) => true,
+            Some(SafetyPolicy::AllFunctionsUnsafe) => false,
+            Some(SafetyPolicy::ReferencesWrapped) => true,
+            Some(SafetyPolicy::AllowlistedSafe) => true,
+            None => self.file_default == SafetyMode::Safe,
+        }
+    }
+}
+
+/// Under the `ReferencesWrapped` policy, a function that takes a raw
+/// pointer/reference parameter must carry an explicit lifetime annotation
+/// for that parameter (looked up via `HeaderCache::get_signature`). This
+/// flags functions that don't, rather than silently skipping them.
+pub fn find_unannotated_reference_functions(
+    context: &SafetyContext,
+    header_cache: &super::HeaderCache,
+    function_names: &[String],
+) -> Vec<String> {
+    let mut flagged = Vec::new();
+
+    if context.policy != Some(SafetyPolicy::ReferencesWrapped) {
+        return flagged;
+    }
+
+    for name in function_names {
+        // An explicit per-function override opts a function out of the
+        // policy check entirely.
+        if context.function_overrides.iter().any(|(n, _)| n == name) {
+            continue;
+        }
+
+        if let Some(signature) = header_cache.get_signature(name) {
+            let has_unannotated_param = signature
+                .param_lifetimes
+                .iter()
+                .any(|lifetime| lifetime.is_none());
+
+            if has_unannotated_param {
+                flagged.push(name.clone());
+            }
+        }
     }
+
+    flagged
 }
 
-/// Parse safety annotations from a C++ file using the unified rule:
-/// @safe/@unsafe attaches to the next statement/block/function/namespace
+/// Parse safety annotations from a C++ file by walking the clang AST.
+///
+/// `@safe`/`@unsafe` comments attached directly to a `FunctionDecl`/`Method`
+/// apply only to that function; the same comment attached to a `Namespace`
+/// or `ClassDecl`/`StructDecl` propagates to every member it contains,
+/// unless a member carries its own overriding annotation. This replaces the
+/// old line-based heuristic, which broke on templates, multi-line
+/// signatures, trailing return types, operator overloads, and nested
+/// namespaces.
 pub fn parse_safety_annotations(path: &Path) -> Result<SafetyContext, String> {
+    let mut context = SafetyContext::new();
+    context.policy = find_safety_policy_directive(path)?;
+
+    let clang = Clang::new()
+        .map_err(|e| format!("Failed to initialize Clang: {:?}", e))?;
+    let index = Index::new(&clang, false, false);
+
+    let args = ["-std=c++17", "-xc++", "-fparse-all-comments", "-Wno-everything"];
+    let tu = index
+        .parser(path)
+        .arguments(&args)
+        .parse()
+        .map_err(|e| format!("Failed to parse file for safety annotations: {:?}", e))?;
+
+    let source_lines: Vec<String> = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file for block safety scanning: {}", e))?
+        .lines()
+        .map(|line| line.to_string())
+        .collect();
+
+    let root = tu.get_entity();
+    for child in root.get_children() {
+        walk_safety_entity(&child, None, &source_lines, &mut context);
+    }
+
+    Ok(context)
+}
+
+/// Scan for a translation-unit-wide `// @safe-policy: ...` directive. This
+/// is deliberately a lightweight line scan rather than an AST walk: the
+/// directive is a pragma about the whole file, not attached to any entity.
+fn find_safety_policy_directive(path: &Path) -> Result<Option<SafetyPolicy>, String> {
     let file = File::open(path)
         .map_err(|e| format!("Failed to open file for safety parsing: {}", e))?;
-    
-    let reader = BufReader::new(file);
-    let mut context = SafetyContext::new();
-    let mut pending_annotation: Option<SafetyMode> = None;
-    let mut in_comment_block = false;
-    let mut _current_line = 0;
-    
-    let mut accumulated_line = String::new();
-    let mut accumulating_for_annotation = false;
-    
-    for line_result in reader.lines() {
-        _current_line += 1;
+
+    for line_result in BufReader::new(file).lines() {
         let line = line_result.map_err(|e| format!("Failed to read line: {}", e))?;
         let trimmed = line.trim();
-        
-        // Handle multi-line comments
-        if in_comment_block {
-            if trimmed.contains("*/") {
-                in_comment_block = false;
-            }
-            // Check for annotations in multi-line comments
-            if trimmed.contains("@safe") {
-                pending_annotation = Some(SafetyMode::Safe);
-            } else if trimmed.contains("@unsafe") {
-                pending_annotation = Some(SafetyMode::Unsafe);
-            }
+        if !trimmed.starts_with("//") {
             continue;
         }
-        
-        // Check for comment start
-        if trimmed.starts_with("/*") {
-            in_comment_block = true;
-            if trimmed.contains("@safe") {
-                pending_annotation = Some(SafetyMode::Safe);
-            } else if trimmed.contains("@unsafe") {
-                pending_annotation = Some(SafetyMode::Unsafe);
+        if let Some(directive) = trimmed.trim_start_matches('/').trim().strip_prefix("@safe-policy:") {
+            if let Some(policy) = SafetyPolicy::from_directive(directive) {
+                return Ok(Some(policy));
             }
-            continue;
         }
-        
-        // Check single-line comments
-        if trimmed.starts_with("//") {
-            if trimmed.contains("@safe") {
-                pending_annotation = Some(SafetyMode::Safe);
-            } else if trimmed.contains("@unsafe") {
-                pending_annotation = Some(SafetyMode::Unsafe);
+    }
+
+    Ok(None)
+}
+
+/// Recursively attach `@safe`/`@unsafe` annotations to functions, propagating
+/// namespace/class-level annotations down to members that don't override them.
+fn walk_safety_entity(entity: &Entity, inherited: Option<SafetyMode>, source_lines: &[String], context: &mut SafetyContext) {
+    let own_annotation = parse_entity_safety(entity);
+
+    match entity.get_kind() {
+        EntityKind::Namespace => {
+            // A namespace-level annotation sets the file-wide default,
+            // mirroring the historical behavior where `@safe`/`@unsafe` on a
+            // namespace applies to everything it contains.
+            if let Some(mode) = own_annotation {
+                context.file_default = mode;
+            }
+            let effective = own_annotation.or(inherited);
+            for child in entity.get_children() {
+                walk_safety_entity(&child, effective, source_lines, context);
             }
-            continue;
         }
-        
-        // Skip empty lines and preprocessor directives
-        if trimmed.is_empty() || trimmed.starts_with("#") {
-            continue;
+        EntityKind::ClassDecl | EntityKind::StructDecl => {
+            let effective = own_annotation.or(inherited);
+            for child in entity.get_children() {
+                walk_safety_entity(&child, effective, source_lines, context);
+            }
         }
-        
-        // If we have a pending annotation, start accumulating
-        if pending_annotation.is_some() && !accumulating_for_annotation {
-            accumulated_line.clear();
-            accumulating_for_annotation = true;
+        EntityKind::FunctionDecl | EntityKind::Method if entity.is_definition() => {
+            let effective = own_annotation.or(inherited);
+            if let Some(mode) = effective {
+                context.function_overrides.push((qualified_name(entity), mode));
+                if let Some(range) = entity_line_range(entity) {
+                    context.regions.push(SafetyRegion { start_line: range.0, end_line: range.1, mode });
+                }
+            }
+
+            // A function's own annotation (or none at all) doesn't stop a
+            // nested `{}` block inside its body from carrying a narrower
+            // override of its own -- walk the body looking for those.
+            for child in entity.get_children() {
+                walk_block_entity(&child, effective, source_lines, context);
+            }
         }
-        
-        // Only accumulate if we're looking for annotation target
-        if accumulating_for_annotation {
-            if !accumulated_line.is_empty() {
-                accumulated_line.push(' ');
+        _ => {
+            for child in entity.get_children() {
+                walk_safety_entity(&child, inherited, source_lines, context);
             }
-            accumulated_line.push_str(trimmed);
-            
-            // Check if we have a complete function declaration (has parentheses)
-            let should_check_annotation = accumulated_line.contains('(') && 
-                                         (accumulated_line.contains(')') || accumulated_line.contains('{'));
-            
-            // If we have a pending annotation and a complete declaration, apply it
-            if should_check_annotation {
-                if let Some(annotation) = pending_annotation.take() {
-                    eprintln!("DEBUG SAFETY: Applying {:?} annotation to: {}", annotation, &accumulated_line);
-                    // Check what kind of code element follows
-                    if accumulated_line.starts_with("namespace") || 
-                       (accumulated_line.contains("namespace") && !accumulated_line.contains("using")) {
-                        // Namespace declaration - applies to whole namespace contents
-                        context.file_default = annotation;
-                        eprintln!("DEBUG SAFETY: Set file default to {:?} (namespace)", annotation);
-                    } else if is_function_declaration(&accumulated_line) {
-                        // Function declaration - extract function name and apply ONLY to this function
-                        if let Some(func_name) = extract_function_name(&accumulated_line) {
-                            context.function_overrides.push((func_name.clone(), annotation));
-                            eprintln!("DEBUG SAFETY: Set function '{}' to {:?}", func_name, annotation);
-                        }
-                    } else {
-                        // Any other code - annotation was consumed but doesn't apply to whole file
-                        // It only applied to this single statement/declaration
-                        eprintln!("DEBUG SAFETY: Annotation consumed by single statement: {}", &accumulated_line);
-                    }
-                    accumulated_line.clear();
-                    accumulating_for_annotation = false;
-                }
+        }
+    }
+}
+
+/// Walk a function body looking for `{}` blocks (`CompoundStmt`) that carry
+/// their own `// @safe`/`// @unsafe` comment on the line immediately above
+/// their opening brace -- clang doesn't associate doc comments with
+/// statements the way it does with declarations, so this reads the source
+/// line directly instead.
+fn walk_block_entity(entity: &Entity, inherited: Option<SafetyMode>, source_lines: &[String], context: &mut SafetyContext) {
+    let mut effective = inherited;
+
+    if entity.get_kind() == EntityKind::CompoundStmt {
+        if let Some(range) = entity_line_range(entity) {
+            if let Some(mode) = preceding_line_annotation(range.0, source_lines) {
+                effective = Some(mode);
+                context.regions.push(SafetyRegion { start_line: range.0, end_line: range.1, mode });
             }
         }
     }
-    
-    Ok(context)
+
+    for child in entity.get_children() {
+        walk_block_entity(&child, effective, source_lines, context);
+    }
 }
 
-/// Check if a line looks like a function declaration
-fn is_function_declaration(line: &str) -> bool {
-    // Simple heuristic - contains parentheses and common return types
-    // This is simplified and could be improved
-    let has_parens = line.contains('(') && line.contains(')');
-    let has_type = line.contains("void") || line.contains("int") || 
-                   line.contains("bool") || line.contains("auto") ||
-                   line.contains("const") || line.contains("static");
-    
-    has_parens && (has_type || line.contains("::"))
+/// `(start_line, end_line)` of `entity`'s source extent, or `None` if the
+/// entity has no associated range (e.g. it's implicit/compiler-generated).
+fn entity_line_range(entity: &Entity) -> Option<(u32, u32)> {
+    let range = entity.get_range()?;
+    let start = range.get_start().get_file_location().line;
+    let end = range.get_end().get_file_location().line;
+    Some((start, end))
 }
 
-/// Extract function name from a declaration line
-fn extract_function_name(line: &str) -> Option<String> {
-    // Find the function name before the opening parenthesis
-    if let Some(paren_pos) = line.find('(') {
-        let before_paren = &line[..paren_pos];
-        // Split by whitespace and get the last identifier
-        let parts: Vec<&str> = before_paren.split_whitespace().collect();
-        if let Some(last) = parts.last() {
-            // Remove any qualifiers like * or &
-            let name = last.trim_start_matches('*').trim_start_matches('&');
-            if !name.is_empty() {
-                return Some(name.to_string());
+/// Whether the (1-based) source line immediately above `line` is a
+/// `// @safe`/`// @unsafe` comment.
+fn preceding_line_annotation(line: u32, source_lines: &[String]) -> Option<SafetyMode> {
+    let index = line.checked_sub(2)?; // line above `line`, 0-based
+    let trimmed = source_lines.get(index as usize)?.trim();
+    if !trimmed.starts_with("//") {
+        return None;
+    }
+    if trimmed.contains("@safe") {
+        Some(SafetyMode::Safe)
+    } else if trimmed.contains("@unsafe") {
+        Some(SafetyMode::Unsafe)
+    } else {
+        None
+    }
+}
+
+/// Build a fully-qualified name (e.g. `myapp::Widget::run`) by walking
+/// semantic parents, so overloaded functions in different namespaces or
+/// classes don't collide in `function_overrides`.
+pub(crate) fn qualified_name(entity: &Entity) -> String {
+    let mut parts = vec![entity.get_name().unwrap_or_else(|| "anonymous".to_string())];
+
+    let mut current = entity.get_semantic_parent();
+    while let Some(parent) = current {
+        match parent.get_kind() {
+            EntityKind::Namespace | EntityKind::ClassDecl | EntityKind::StructDecl => {
+                parts.push(parent.get_name().unwrap_or_else(|| "anonymous".to_string()));
+                current = parent.get_semantic_parent();
             }
+            _ => break,
         }
     }
-    None
+
+    parts.reverse();
+    parts.join("::")
 }
 
 /// Parse safety annotation from entity comment (for clang AST)
-#[allow(dead_code)]
 pub fn parse_entity_safety(entity: &Entity) -> Option<SafetyMode> {
     if let Some(comment) = entity.get_comment() {
         if comment.contains("@safe") {
@@ -243,6 +525,55 @@ void explicit_unsafe() {}
         assert!(!context.should_check_function("explicit_unsafe"));
     }
     
+    #[test]
+    fn test_class_safe_annotation_uses_qualified_name() {
+        let code = r#"
+// @safe
+class Widget {
+public:
+    void run() {}
+};
+"#;
+
+        let mut file = NamedTempFile::with_suffix(".cpp").unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let context = parse_safety_annotations(file.path()).unwrap();
+        assert!(context
+            .function_overrides
+            .iter()
+            .any(|(name, mode)| name == "Widget::run" && *mode == SafetyMode::Safe));
+    }
+
+    #[test]
+    fn test_safe_policy_directive() {
+        let code = r#"
+// @safe-policy: references-wrapped
+
+void func(int& x) {}
+"#;
+
+        let mut file = NamedTempFile::with_suffix(".cpp").unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let context = parse_safety_annotations(file.path()).unwrap();
+        assert_eq!(context.policy, Some(SafetyPolicy::ReferencesWrapped));
+        // ReferencesWrapped treats functions as checked-safe by default.
+        assert!(context.should_check_function("func"));
+    }
+
+    #[test]
+    fn test_blocked_function_overrides_safe_policy() {
+        let mut context = SafetyContext::new();
+        context.policy = Some(SafetyPolicy::AllFunctionsSafe);
+        context.set_block_list(vec!["legacy::*".to_string()]);
+
+        assert!(context.should_check_function("widget::run"));
+        assert!(!context.should_check_function("legacy::run"));
+    }
+
     #[test]
     fn test_first_code_element_annotation() {
         let code = r#"
@@ -260,4 +591,128 @@ void func() {}
         // @safe only applies to the next element (global_var), not the whole file
         assert_eq!(context.file_default, SafetyMode::Default);
     }
+
+    #[test]
+    fn test_unsafe_block_inside_safe_function_is_scoped() {
+        let code = r#"
+// @safe
+void func() {
+    int a = 1;
+    // @unsafe
+    {
+        int* p = &a;
+    }
+    int b = 2;
+}
+"#;
+
+        let mut file = NamedTempFile::with_suffix(".cpp").unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let context = parse_safety_annotations(file.path()).unwrap();
+        // Line 4 (`int a = 1;`) and line 9 (`int b = 2;`) are in the
+        // function's own @safe region; line 7 (inside the nested block) is
+        // in the narrower @unsafe region and should resolve to unchecked.
+        assert!(context.is_safe_at(4));
+        assert!(!context.is_safe_at(7));
+        assert!(context.is_safe_at(9));
+    }
+
+    #[test]
+    fn test_safe_block_inside_unsafe_function_is_scoped() {
+        let code = r#"
+// @unsafe
+void func() {
+    int* p = nullptr;
+    // @safe
+    {
+        int a = 1;
+    }
+}
+"#;
+
+        let mut file = NamedTempFile::with_suffix(".cpp").unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let context = parse_safety_annotations(file.path()).unwrap();
+        assert!(!context.is_safe_at(4));
+        assert!(context.is_safe_at(7));
+    }
+
+    #[test]
+    fn test_is_safe_at_falls_back_to_file_default_outside_any_region() {
+        let mut context = SafetyContext::new();
+        context.file_default = SafetyMode::Safe;
+        context.regions.push(SafetyRegion { start_line: 10, end_line: 20, mode: SafetyMode::Unsafe });
+
+        assert!(!context.is_safe_at(15));
+        assert!(context.is_safe_at(5));
+    }
+
+    #[test]
+    fn test_allowlisted_safe_directive_parses() {
+        let code = r#"
+// @safe-policy: allowlisted-safe
+
+void func() {}
+"#;
+
+        let mut file = NamedTempFile::with_suffix(".cpp").unwrap();
+        file.write_all(code.as_bytes()).unwrap();
+        file.flush().unwrap();
+
+        let context = parse_safety_annotations(file.path()).unwrap();
+        assert_eq!(context.policy, Some(SafetyPolicy::AllowlistedSafe));
+    }
+
+    #[test]
+    fn test_call_allowlist_banned_wins_over_safe_function() {
+        let allowlist = CallAllowlist {
+            safe_functions: vec!["strcpy".to_string()],
+            safe_namespaces: vec![],
+            banned_functions: vec!["strcpy".to_string()],
+        };
+
+        assert!(!allowlist.permits("strcpy"));
+    }
+
+    #[test]
+    fn test_call_allowlist_matches_namespace_glob() {
+        let allowlist = CallAllowlist {
+            safe_functions: vec![],
+            safe_namespaces: vec!["myapp::*".to_string()],
+            banned_functions: vec![],
+        };
+
+        assert!(allowlist.permits("myapp::wrapper"));
+        assert!(!allowlist.permits("other::wrapper"));
+    }
+
+    #[test]
+    fn test_call_allowlist_from_toml_parses_lists() {
+        let toml = r#"
+[allowlist]
+safe_functions = ["my_safe_wrapper", "another_one"]
+safe_namespaces = ["trusted::*"]
+banned_functions = ["gets", "strcpy"]
+"#;
+
+        let allowlist = CallAllowlist::from_toml(toml).unwrap();
+        assert!(allowlist.permits("my_safe_wrapper"));
+        assert!(allowlist.permits("trusted::thing"));
+        assert!(!allowlist.permits("gets"));
+        assert!(!allowlist.permits("random_func"));
+    }
+
+    #[test]
+    fn test_call_allowlist_from_toml_rejects_unknown_key() {
+        let toml = r#"
+[allowlist]
+not_a_real_key = ["x"]
+"#;
+
+        assert!(CallAllowlist::from_toml(toml).is_err());
+    }
 }
\ No newline at end of file
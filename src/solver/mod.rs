@@ -1,59 +0,0 @@
-use z3::{Context, Solver};
-use std::collections::HashMap;
-
-#[allow(dead_code)]
-pub struct ConstraintSolver<'ctx> {
-    solver: Solver<'ctx>,
-}
-
-impl<'ctx> ConstraintSolver<'ctx> {
-    #[allow(dead_code)]
-    pub fn new(context: &'ctx Context) -> Self {
-        let solver = Solver::new(context);
-        Self { solver }
-    }
-    
-    #[allow(dead_code)]
-    pub fn add_lifetime_constraint(&mut self, constraint: LifetimeConstraint) {
-        // Convert lifetime constraints to Z3 assertions
-        match constraint {
-            LifetimeConstraint::Outlives { longer: _, shorter: _ } => {
-                // Add SMT constraint: longer >= shorter
-            }
-            LifetimeConstraint::MustBeValid { lifetime: _, point: _ } => {
-                // Add SMT constraint: lifetime.start <= point <= lifetime.end
-            }
-        }
-    }
-    
-    #[allow(dead_code)]
-    pub fn solve(&self) -> Result<Solution, String> {
-        match self.solver.check() {
-            z3::SatResult::Sat => {
-                // Extract model and return solution
-                Ok(Solution {
-                    lifetimes: HashMap::new(),
-                })
-            }
-            z3::SatResult::Unsat => {
-                Err("Lifetime constraints are unsatisfiable".to_string())
-            }
-            z3::SatResult::Unknown => {
-                Err("Could not determine satisfiability".to_string())
-            }
-        }
-    }
-}
-
-#[derive(Debug)]
-#[allow(dead_code)]
-pub enum LifetimeConstraint {
-    Outlives { longer: String, shorter: String },
-    MustBeValid { lifetime: String, point: usize },
-}
-
-#[derive(Debug)]
-#[allow(dead_code)]
-pub struct Solution {
-    pub lifetimes: HashMap<String, (usize, usize)>,
-}
\ No newline at end of file
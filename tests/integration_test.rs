@@ -27,6 +27,119 @@ fn create_temp_cpp_file(content: &str) -> NamedTempFile {
     file
 }
 
+/// An expected violation parsed out of a fixture's `//~ ERROR <text>`
+/// comments: the 1-indexed source line it's pinned to, and a fragment the
+/// reported message must contain.
+#[derive(Debug, PartialEq, Eq)]
+struct ExpectedError {
+    line: u32,
+    fragment: String,
+}
+
+/// Parse `//~ ERROR <text>` and `//~^ ERROR <text>` annotations out of a
+/// fixture's source. `//~` pins the error to the line it's written on;
+/// `//~^` (borrowed from rustc's UI test convention) pins it to the line
+/// above instead, for when the annotation doesn't fit on the same line as
+/// the code it's describing.
+fn parse_expected_errors(source: &str) -> Vec<ExpectedError> {
+    let annotation = regex::Regex::new(r"//~(\^?)\s*ERROR\s+(.+?)\s*$").expect("valid regex");
+
+    let mut expected = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let Some(captures) = annotation.captures(line) else {
+            continue;
+        };
+        let line_number = (index + 1) as u32;
+        let target_line = if &captures[1] == "^" {
+            line_number - 1
+        } else {
+            line_number
+        };
+        expected.push(ExpectedError {
+            line: target_line,
+            fragment: captures[2].to_string(),
+        });
+    }
+    expected
+}
+
+/// Parse `(line, message)` pairs out of the analyzer's rendered output by
+/// pairing each `--> file:line:col` location header with the diagnostic
+/// message printed on the line just above it (see `BorrowCheckDiagnostic`'s
+/// `Display` impl in `src/diagnostics/mod.rs`).
+fn parse_actual_violations(output: &str) -> Vec<(u32, String)> {
+    let location = regex::Regex::new(r"-->\s*\S+:(\d+):\d+").expect("valid regex");
+
+    let lines: Vec<&str> = output.lines().collect();
+    let mut actual = Vec::new();
+    for (index, line) in lines.iter().enumerate() {
+        let Some(captures) = location.captures(line) else {
+            continue;
+        };
+        let line_number: u32 = captures[1].parse().expect("captured digits");
+        let message = index
+            .checked_sub(1)
+            .and_then(|prev| lines.get(prev))
+            .copied()
+            .unwrap_or("");
+        actual.push((line_number, strip_ansi(message)));
+    }
+    actual
+}
+
+/// Strip `colored`'s ANSI escape codes so fragment matching doesn't have to
+/// care whether the analyzer's output went to a terminal.
+fn strip_ansi(text: &str) -> String {
+    let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").expect("valid regex");
+    ansi.replace_all(text, "").into_owned()
+}
+
+/// Run the analyzer over a fixture containing `//~ ERROR` annotations and
+/// assert that expectations and reality line up exactly: every annotated
+/// line produced a matching violation, and the analyzer raised no
+/// violation on a line the fixture didn't annotate. On mismatch, panics
+/// with a line-by-line expected-vs-actual diff instead of a bare `assert`.
+fn check_fixture(cpp_source: &str) {
+    let expected = parse_expected_errors(cpp_source);
+    let temp_file = create_temp_cpp_file(cpp_source);
+    let (_success, output) = run_analyzer(temp_file.path());
+    let actual = parse_actual_violations(&output);
+
+    let mut unmatched_expected: Vec<&ExpectedError> = expected.iter().collect();
+    let mut unmatched_actual: Vec<&(u32, String)> = actual.iter().collect();
+
+    unmatched_expected.retain(|exp| {
+        let position = unmatched_actual
+            .iter()
+            .position(|(line, message)| *line == exp.line && message.contains(&exp.fragment));
+        match position {
+            Some(index) => {
+                unmatched_actual.remove(index);
+                false
+            }
+            None => true,
+        }
+    });
+
+    if !unmatched_expected.is_empty() || !unmatched_actual.is_empty() {
+        let mut diff = String::from("fixture annotations don't match analyzer output:\n");
+        for exp in &unmatched_expected {
+            diff.push_str(&format!(
+                "  - expected ERROR on line {} containing {:?}, but no such violation was reported\n",
+                exp.line, exp.fragment
+            ));
+        }
+        for (line, message) in &unmatched_actual {
+            diff.push_str(&format!(
+                "  - unexpected violation on line {}: {:?} (add a `//~ ERROR` annotation or fix the analyzer)\n",
+                line, message
+            ));
+        }
+        diff.push_str(&format!("\nfull analyzer output:\n{}", output));
+        panic!("{}", diff);
+    }
+}
+
 #[test]
 fn test_valid_cpp_code_passes() {
     let code = r#"
@@ -117,12 +230,12 @@ fn test_multiple_mutable_borrows() {
         ref2 = 20;
     }
     "#;
-    
+
     let temp_file = create_temp_cpp_file(code);
     let (success, output) = run_analyzer(temp_file.path());
-    
+
     assert!(output.contains("violation"), "Should detect violations");
-    assert!(output.contains("Cannot create mutable reference") && output.contains("already mutably borrowed"), 
+    assert!(output.contains("Cannot create mutable reference") && output.contains("already mutably borrowed"),
             "Should detect multiple mutable borrows. Output: {}", output);
 }
 
@@ -170,10 +283,10 @@ fn test_mixed_const_and_mutable_refs() {
         int& mut_ref = value;  // Should fail - can't have mutable with const
     }
     "#;
-    
+
     let temp_file = create_temp_cpp_file(code);
     let (_success, output) = run_analyzer(temp_file.path());
-    
+
     assert!(output.contains("violation"), "Should detect mixed reference violation");
     assert!(output.contains("Cannot create mutable reference") && output.contains("already immutably borrowed"),
             "Should detect mixed borrows. Output: {}", output);
@@ -188,10 +301,10 @@ fn test_mutable_then_const_refs() {
         const int& const_ref = value;  // Should fail - can't have const with mutable
     }
     "#;
-    
+
     let temp_file = create_temp_cpp_file(code);
     let (_success, output) = run_analyzer(temp_file.path());
-    
+
     assert!(output.contains("violation"), "Should detect mixed reference violation");
     assert!(output.contains("Cannot create immutable reference") && output.contains("already mutably borrowed"),
             "Should detect mixed borrows. Output: {}", output);
@@ -326,6 +439,8 @@ fn test_complex_reference_pattern() {
     assert!(output.contains("violation"), "Should detect violation");
     assert!(output.contains("'b'") && output.contains("already mutably borrowed"),
             "Should only error on variable b. Output: {}", output);
+    assert!(output.contains("'mut_b' was borrowed at statement"),
+            "Should point to the originating borrow 'mut_b'. Output: {}", output);
 }
 
 #[test]
@@ -480,8 +595,92 @@ void test_env() {
     let stderr = String::from_utf8_lossy(&output.stderr);
     let full_output = format!("{}{}", stdout, stderr);
     
-    assert!(output.status.success(), 
+    assert!(output.status.success(),
             "Should successfully use environment include paths. Output: {}", full_output);
     assert!(full_output.contains("Found 1 include path(s) from environment"),
             "Should report finding environment paths. Output: {}", full_output);
+}
+
+// The checks below report real source locations (unlike the borrow-checker
+// messages above, which main.rs currently attaches to line 0), so they're
+// written against `check_fixture` instead of `output.contains(...)`: each
+// `//~ ERROR` annotation is pinned to the exact line it expects, and the
+// harness also fails if the analyzer reports a violation nothing annotated.
+#[test]
+fn test_unsafe_call_from_safe_function_is_pinned_to_its_line() {
+    let code = r#"
+    void unchecked_helper() {
+    }
+
+    // @safe
+    void caller() {
+        unchecked_helper();  //~ ERROR requires unsafe context
+    }
+    "#;
+
+    check_fixture(code);
+}
+
+#[test]
+fn test_check_fixture_catches_an_unannotated_violation() {
+    // `check_fixture` itself should fail loudly when a fixture is missing
+    // an annotation for a violation the analyzer actually reports, rather
+    // than silently passing like a bare `output.contains(...)` would.
+    let code = r#"
+    void unchecked_helper() {
+    }
+
+    // @safe
+    void caller() {
+        unchecked_helper();
+    }
+    "#;
+
+    let result = std::panic::catch_unwind(|| check_fixture(code));
+    assert!(result.is_err(), "an unannotated violation should fail the fixture check");
+}
+
+#[test]
+fn test_error_format_json_emits_typed_diagnostic_fields() {
+    let code = r#"
+    void unchecked_helper() {
+    }
+
+    // @safe
+    void caller() {
+        unchecked_helper();
+    }
+    "#;
+
+    let temp_file = create_temp_cpp_file(code);
+    let output = Command::new("cargo")
+        .args(&["run", "--quiet", "--", "--error-format", "json", temp_file.path().to_str().unwrap()])
+        .env("Z3_SYS_Z3_HEADER", "/opt/homebrew/include/z3.h")
+        .env("DYLD_LIBRARY_PATH", "/opt/homebrew/Cellar/llvm/19.1.7/lib")
+        .output()
+        .expect("Failed to execute analyzer");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let report: serde_json::Value = serde_json::from_str(stdout.trim())
+        .unwrap_or_else(|e| panic!("--error-format json should emit valid JSON: {e}\noutput: {stdout}"));
+
+    assert_eq!(report["summary"]["violation_count"], 1);
+    let diagnostics = report["diagnostics"].as_array().expect("diagnostics array");
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0]["level"], "error");
+    assert_eq!(diagnostics[0]["code"], "RUSTYCPP-E0002");
+    assert_eq!(diagnostics[0]["kind"], "unsafe_call");
+    assert_eq!(diagnostics[0]["symbol"], "unchecked_helper");
+    assert!(diagnostics[0]["message"].as_str().unwrap().contains("unchecked_helper"));
+    assert_eq!(diagnostics[0]["spans"][0]["line_start"], 7);
+    assert_eq!(diagnostics[0]["suggested_replacement"], "unsafe { unchecked_helper(); }");
+}
+
+#[test]
+fn test_parse_expected_errors_handles_caret_referring_to_previous_line() {
+    let source = "int& mut_ref = value;\n//~^ ERROR already mutably borrowed\n";
+    let expected = parse_expected_errors(source);
+    assert_eq!(expected.len(), 1);
+    assert_eq!(expected[0].line, 1);
+    assert_eq!(expected[0].fragment, "already mutably borrowed");
 }
\ No newline at end of file
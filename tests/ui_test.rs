@@ -0,0 +1,113 @@
+//! Golden-file ("UI") tests. Each fixture in `tests/ui/*.cpp` has a sibling
+//! `*.stderr` file holding the analyzer's expected full output; this runner
+//! diffs one against the other. Adding a new regression case is just
+//! dropping in a `.cpp` file and its matching `.stderr`, and any change in
+//! diagnostic wording shows up as a reviewable diff instead of a silent
+//! `output.contains(...)` pass.
+//!
+//! Set `BLESS=1` to (re)generate the `.stderr` files from the analyzer's
+//! current output instead of checking them:
+//!
+//!     BLESS=1 cargo test --test ui_test
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn ui_dir() -> &'static Path {
+    Path::new("tests/ui")
+}
+
+/// Run the analyzer over a fixture, passing its path exactly as given (a
+/// path relative to the crate root, not a copy in a temp directory) so the
+/// `-->` location lines in its output are already stable across machines.
+///
+/// A fixture whose `.cpp` needs extra CLI flags (e.g. `--safety
+/// references-wrapped`) lists one flag per line in a sibling `.args` file;
+/// most fixtures have no such file and just run with no extra flags.
+fn run_analyzer(cpp_file: &Path) -> String {
+    let args_path = cpp_file.with_extension("args");
+    let extra_args: Vec<String> = fs::read_to_string(&args_path)
+        .map(|contents| contents.lines().map(str::to_string).filter(|line| !line.is_empty()).collect())
+        .unwrap_or_default();
+
+    let output = Command::new("cargo")
+        .args(&["run", "--quiet", "--", cpp_file.to_str().unwrap()])
+        .args(&extra_args)
+        .env("Z3_SYS_Z3_HEADER", "/opt/homebrew/include/z3.h")
+        .env("DYLD_LIBRARY_PATH", "/opt/homebrew/Cellar/llvm/19.1.7/lib")
+        .output()
+        .expect("Failed to execute analyzer");
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    normalize(&format!("{}{}", stdout, stderr))
+}
+
+/// Strip everything about the output that isn't the analyzer's own voice:
+/// `colored`'s ANSI escapes (present when `cargo test` gives the child a
+/// tty but absent otherwise, so leaving them in would make the snapshot
+/// depend on how the test happens to be run), and the `Compiling`/
+/// `Finished`/`Running` lines `cargo run` prints to stderr on its own
+/// whenever it has to rebuild first.
+fn normalize(output: &str) -> String {
+    let ansi = regex::Regex::new(r"\x1b\[[0-9;]*m").expect("valid regex");
+    let cargo_noise = regex::Regex::new(r"^\s*(Compiling|Finished|Running|Updating|Downloading|Downloaded)")
+        .expect("valid regex");
+
+    ansi.replace_all(output, "")
+        .lines()
+        .filter(|line| !cargo_noise.is_match(line))
+        .collect::<Vec<_>>()
+        .join("\n")
+        + "\n"
+}
+
+#[test]
+fn ui_fixtures_match_their_committed_stderr() {
+    let bless = std::env::var_os("BLESS").is_some();
+    let mut failures = Vec::new();
+
+    let entries = fs::read_dir(ui_dir()).expect("tests/ui should exist");
+    let mut fixtures: Vec<_> = entries
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|ext| ext.to_str()) == Some("cpp"))
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty(), "tests/ui should contain at least one .cpp fixture");
+
+    for cpp_path in fixtures {
+        let stderr_path = cpp_path.with_extension("stderr");
+        let actual = run_analyzer(&cpp_path);
+
+        if bless {
+            fs::write(&stderr_path, &actual).expect("failed to write .stderr expectation");
+            continue;
+        }
+
+        let expected = fs::read_to_string(&stderr_path).unwrap_or_else(|_| {
+            panic!(
+                "missing {} -- run with BLESS=1 to generate it",
+                stderr_path.display()
+            )
+        });
+
+        if actual != expected {
+            failures.push(format!(
+                "{}:\n--- expected ---\n{}--- actual ---\n{}",
+                cpp_path.display(),
+                expected,
+                actual
+            ));
+        }
+    }
+
+    if !failures.is_empty() {
+        panic!(
+            "{} ui fixture(s) don't match their .stderr (rerun with BLESS=1 to update):\n\n{}",
+            failures.len(),
+            failures.join("\n")
+        );
+    }
+}